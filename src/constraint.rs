@@ -0,0 +1,60 @@
+//! Dose-volume constraints.
+//!
+//! This module provides the [`Constraint`] struct for expressing clinical protocol
+//! constraints such as "Rectum V70Gy <= 15%".
+
+use crate::Metric;
+
+/// Represents the comparison applied between a metric value and a threshold.
+///
+/// # Variants
+/// - `LessThan`: The value must be smaller than the threshold
+/// - `LessOrEqual`: The value must be smaller than or equal to the threshold
+/// - `GreaterThan`: The value must be larger than the threshold
+/// - `GreaterOrEqual`: The value must be larger than or equal to the threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Comparison {
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// Applies the comparison to a value and a threshold.
+    ///
+    /// # Parameters
+    /// - `value`: The value to compare
+    /// - `threshold`: The threshold to compare against
+    ///
+    /// # Returns
+    /// `true` if the comparison holds
+    pub fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::LessThan => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::GreaterThan => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+        }
+    }
+}
+
+/// Represents a constraint on a metric of a structure, e.g. "Rectum V70Gy <= 15%".
+///
+/// The metric is evaluated with [`Metric::evaluate`], so volume arguments and results
+/// are fractions of the structure volume and doses are in the dose unit of the DVH.
+///
+/// # Fields
+/// - `structure`: The structure name
+/// - `metric`: The metric to evaluate, e.g. `Metric::Vx(70.0)`
+/// - `comparison`: The comparison between the metric value and the threshold
+/// - `threshold`: The threshold, e.g. 0.15
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constraint {
+    pub structure: String,
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
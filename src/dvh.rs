@@ -1,6 +1,6 @@
 use std::fmt::{Display, Formatter};
 use crate::traits::DvhCheck;
-use crate::{Error, MaxDose};
+use crate::{Error, MaxDose, MeanDose, Metric, MinDose, RoiType};
 
 /// Performs linear interpolation between two points.
 ///
@@ -20,6 +20,32 @@ fn linear_interpolation(x: f64, x0: f64, x1: f64, y0: f64, y1: f64) -> f64 {
     (x - x0) * (y1 - y0) / (x1 - x0) + y0
 }
 
+/// Maximum distance in units in the last place (ULP) for a query to match a data point.
+const EXACT_MATCH_ULPS: u64 = 4;
+
+/// Checks whether two values are equal within a number of units in the last place (ULP).
+///
+/// # Parameters
+/// - `a`: The first value
+/// - `b`: The second value
+/// - `max_ulps`: The maximum number of representable values between `a` and `b`
+///
+/// # Returns
+/// `true` if `a` and `b` are equal within `max_ulps`, `false` otherwise
+fn ulps_eq(a: f64, b: f64, max_ulps: u64) -> bool {
+    if a == b {
+        return true;
+    }
+    if a.is_sign_negative() != b.is_sign_negative() {
+        return false;
+    }
+    (a.to_bits() as i64 - b.to_bits() as i64).unsigned_abs() <= max_ulps
+}
+
+/// Largest distance outside of [0.0, 1.0] of a percent volume clamped by
+/// [`Dvh::clamp_percent_volumes`] and [`Dvh::deserialize_lenient`].
+pub const PERCENT_CLAMP_TOLERANCE: f64 = 1e-4;
+
 /// Represents the unit type for dose measurements.
 ///
 /// # Variants
@@ -65,6 +91,83 @@ impl Display for VolumeUnit {
     }
 }
 
+/// Represents the interpolation mode used to query a DVH between its data points.
+///
+/// # Variants
+/// - `Linear`: Linear interpolation of dose and volume (default)
+/// - `LogLinear`: Linear interpolation of the dose against the logarithm of the volume,
+///   which follows the exponential fall-off of DVH tails more closely. Falls back to
+///   linear interpolation on segments with a zero volume.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InterpolationMode {
+    #[default]
+    Linear,
+    LogLinear,
+}
+
+impl InterpolationMode {
+    /// Interpolates the volume at `dose` between the points (`d0`, `v0`) and (`d1`, `v1`).
+    fn interpolate_volume(self, dose: f64, d0: f64, d1: f64, v0: f64, v1: f64) -> f64 {
+        match self {
+            InterpolationMode::LogLinear if v0 > 0.0 && v1 > 0.0 => {
+                linear_interpolation(dose, d0, d1, v0.ln(), v1.ln()).exp()
+            }
+            _ => linear_interpolation(dose, d0, d1, v0, v1),
+        }
+    }
+
+    /// Interpolates the dose at `volume` between the points (`v0`, `d0`) and (`v1`, `d1`).
+    fn interpolate_dose(self, volume: f64, v0: f64, v1: f64, d0: f64, d1: f64) -> f64 {
+        match self {
+            InterpolationMode::LogLinear if volume > 0.0 && v0 > 0.0 && v1 > 0.0 => {
+                linear_interpolation(volume.ln(), v0.ln(), v1.ln(), d0, d1)
+            }
+            _ => linear_interpolation(volume, v0, v1, d0, d1),
+        }
+    }
+}
+
+/// Represents the kind of dose-volume histogram.
+///
+/// # Variants
+/// - `Cumulative`: The volume at a dose is the volume receiving at least that dose (default)
+/// - `Differential`: The volume at a dose is the volume of the dose bin at that dose
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DvhKind {
+    #[default]
+    Cumulative,
+    Differential,
+}
+
+/// Represents the convention used to store [Percent](VolumeUnit::Percent) volumes.
+///
+/// Volumes are always kept in the range [0.0, 1.0] in memory; the scale only applies
+/// to the serialized data.
+///
+/// # Variants
+/// - `Unit`: Volumes are stored in the range [0.0, 1.0] (default)
+/// - `Hundred`: Volumes are stored in the range [0.0, 100.0]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PercentScale {
+    #[default]
+    Unit,
+    Hundred,
+}
+
+#[cfg(feature = "serde")]
+impl PercentScale {
+    /// Returns the factor to convert a volume fraction to this scale.
+    fn factor(self) -> f64 {
+        match self {
+            PercentScale::Unit => 1.0,
+            PercentScale::Hundred => 100.0,
+        }
+    }
+}
+
 /// Dose-Volume Histogram (DVH) structure for radiation therapy analysis.
 ///
 /// A DVH represents the relationship between radiation dose and the volume
@@ -75,10 +178,25 @@ impl Display for VolumeUnit {
 /// - `dose_type`: The unit type for dose measurements
 /// - `d`: Vector of dose values
 /// - `v`: Vector of volume values
-///        If the volume type is [Percent](VolumeUnit::Percent), the values are in the range [0.0, 1.0]
+///   If the volume type is [Percent](VolumeUnit::Percent), the values are in the range [0.0, 1.0]
 /// - `is_sorted`: Whether the data is sorted by dose in ascending order
+/// - `roi_type`: Optional clinical role of the structure the DVH belongs to
+/// - `reference_volume_cc`: Optional total structure volume in cc. Methods that need the
+///   structure volume use it when no explicit volume is given.
+/// - `interp_mode`: The interpolation mode used by [`Dvh::dx`] and [`Dvh::vx`]
+/// - `percent_scale`: The convention of [Percent](VolumeUnit::Percent) volumes in the
+///   serialized data. The volumes are converted to the range [0.0, 1.0] when loaded and
+///   back to this convention when saved.
+/// - `kind`: Whether the DVH is cumulative or differential
+/// - `prescription_dose`: Optional prescription dose of the structure in the dose unit of
+///   the DVH, e.g. for targets of a simultaneous integrated boost (SIB) plan. When set, it
+///   takes precedence over the prescription passed to coverage and normalization methods.
 #[derive(Clone, Debug, Default, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "DvhData", into = "DvhData")
+)]
 pub struct Dvh {
     // The unit type for dose
     pub dose_unit: DoseUnit,
@@ -92,8 +210,90 @@ pub struct Dvh {
     // Is the data sorted monotonically incrementally along the dose axis?
     // With serde is enabled, the value is not serialized and deserialized
     // because the input data can't be trusted to be sorted.
-    #[cfg_attr(feature = "serde", serde(skip, default))]
     is_sorted: bool,
+    // Clinical role of the structure (target, OAR, ...), if known
+    pub roi_type: Option<RoiType>,
+    // Total structure volume in cc, used to convert percent volumes to cc
+    pub reference_volume_cc: Option<f64>,
+    // Interpolation mode used by the Dx and Vx queries
+    interp_mode: InterpolationMode,
+    // Convention of percent volumes in the serialized data
+    pub percent_scale: PercentScale,
+    // Cumulative or differential DVH
+    pub kind: DvhKind,
+    // Prescription dose of the structure, if it has its own prescription
+    pub prescription_dose: Option<f64>,
+}
+
+/// Serialized representation of a [Dvh].
+///
+/// [Percent](VolumeUnit::Percent) volumes are stored according to `percent_scale`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DvhData {
+    dose_unit: DoseUnit,
+    volume_unit: VolumeUnit,
+    d: Vec<f64>,
+    v: Vec<f64>,
+    #[serde(default)]
+    roi_type: Option<RoiType>,
+    #[serde(default)]
+    reference_volume_cc: Option<f64>,
+    #[serde(default)]
+    interp_mode: InterpolationMode,
+    #[serde(default)]
+    percent_scale: PercentScale,
+    #[serde(default)]
+    kind: DvhKind,
+    #[serde(default)]
+    prescription_dose: Option<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl From<DvhData> for Dvh {
+    fn from(data: DvhData) -> Self {
+        let mut v = data.v;
+        if data.volume_unit == VolumeUnit::Percent {
+            let factor = data.percent_scale.factor();
+            v.iter_mut().for_each(|v| *v /= factor);
+        }
+        Dvh {
+            dose_unit: data.dose_unit,
+            volume_unit: data.volume_unit,
+            d: data.d,
+            v,
+            is_sorted: false,
+            roi_type: data.roi_type,
+            reference_volume_cc: data.reference_volume_cc,
+            interp_mode: data.interp_mode,
+            percent_scale: data.percent_scale,
+            kind: data.kind,
+            prescription_dose: data.prescription_dose,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Dvh> for DvhData {
+    fn from(dvh: Dvh) -> Self {
+        let mut v = dvh.v;
+        if dvh.volume_unit == VolumeUnit::Percent {
+            let factor = dvh.percent_scale.factor();
+            v.iter_mut().for_each(|v| *v *= factor);
+        }
+        DvhData {
+            dose_unit: dvh.dose_unit,
+            volume_unit: dvh.volume_unit,
+            d: dvh.d,
+            v,
+            roi_type: dvh.roi_type,
+            reference_volume_cc: dvh.reference_volume_cc,
+            interp_mode: dvh.interp_mode,
+            percent_scale: dvh.percent_scale,
+            kind: dvh.kind,
+            prescription_dose: dvh.prescription_dose,
+        }
+    }
 }
 
 impl Dvh {
@@ -111,7 +311,49 @@ impl Dvh {
             d: Default::default(),
             v: Default::default(),
             is_sorted: false,
+            roi_type: None,
+            reference_volume_cc: None,
+            interp_mode: InterpolationMode::Linear,
+            percent_scale: PercentScale::Unit,
+            kind: DvhKind::Cumulative,
+            prescription_dose: None,
+        }
+    }
+
+    /// Creates a new DVH from (dose, volume) pairs.
+    ///
+    /// The pairs are validated like [`Dvh::add`], but the reason for rejecting a pair is
+    /// reported. The DVH is not sorted.
+    ///
+    /// # Parameters
+    /// - `dose_type`: The unit type for dose measurements
+    /// - `volume_type`: The unit type for volume measurements
+    /// - `points`: The (dose, volume) pairs
+    ///
+    /// # Returns
+    /// A new unsorted DVH containing the pairs
+    ///
+    /// # Errors
+    /// Returns the error of the first invalid pair:
+    /// - `Error::NonFiniteValue`: If a dose or volume is NaN or infinite
+    /// - `Error::NegativeDose`: If a dose is negative
+    /// - `Error::NegativeVolume`: If a volume is negative
+    /// - `Error::PercentVolumeOutOfRange`: If a [Percent](VolumeUnit::Percent) volume
+    ///   exceeds 1.0
+    pub fn from_points(
+        dose_type: DoseUnit,
+        volume_type: VolumeUnit,
+        points: &[(f64, f64)],
+    ) -> crate::Result<Dvh> {
+        let mut dvh = Dvh::new(dose_type, volume_type);
+        dvh.d.reserve(points.len());
+        dvh.v.reserve(points.len());
+        for &(d, v) in points {
+            dvh.validate_point(d, v)?;
+            dvh.d.push(d);
+            dvh.v.push(v);
         }
+        Ok(dvh)
     }
 
     /// Returns the number of dose-volume data points in the DVH.
@@ -132,73 +374,106 @@ impl Dvh {
 
     /// Adds a single dose-volume data point to the DVH.
     ///
+    /// This is a thin wrapper around [`Dvh::try_add`] that discards the reason a data
+    /// point was rejected.
+    ///
     /// # Parameters
-    /// - `d`: The dose value (must be non-negative)
-    /// - `v`: The volume value (must be non-negative)
-    ///        If the volume type is [Percent](VolumeUnit::Percent), the values are in the range [0.0, 1.0]
+    /// - `d`: The dose value (must be finite and non-negative)
+    /// - `v`: The volume value (must be finite and non-negative)
+    ///   If the volume type is [Percent](VolumeUnit::Percent), the values are in the range [0.0, 1.0]
     ///
     /// # Returns
-    /// `true` if the data point was added successfully, `false` if either value is negative
+    /// `true` if the data point was added successfully, `false` if either value is negative,
+    /// NaN or infinite
     pub fn add(&mut self, d: f64, v: f64) -> bool {
-        if d < 0.0 {
-            return false;
-        }
-        if v < 0.0 {
-            return false;
-        }
-        if self.volume_unit == VolumeUnit::Percent && v > 1.0 {
-            return false;
-        }
-        self.is_sorted = false;
-        self.d.push(d);
-        self.v.push(v);
-        true
+        self.try_add(d, v).is_ok()
     }
 
     /// Adds multiple dose-volume data points to the DVH from slices.
     ///
+    /// This is a thin wrapper around [`Dvh::try_add_slice`] that discards the reason the
+    /// data points were rejected.
+    ///
     /// # Parameters
-    /// - `d`: Slice of dose values (all must be non-negative)
-    /// - `v`: Slice of volume values (all must be non-negative)
+    /// - `d`: Slice of dose values (all must be finite and non-negative)
+    /// - `v`: Slice of volume values (all must be finite and non-negative)
     ///
     /// # Returns
     /// `true` if all data points were added successfully, `false` if the slices have different
-    /// lengths or if any value is negative
+    /// lengths or if any value is negative, NaN or infinite
     pub fn add_slice(&mut self, d: &[f64], v: &[f64]) -> bool {
+        self.try_add_slice(d, v).is_ok()
+    }
+
+    /// Adds a single dose-volume data point to the DVH, reporting why it was rejected.
+    ///
+    /// # Parameters
+    /// - `d`: The dose value (must be finite and non-negative)
+    /// - `v`: The volume value (must be finite and non-negative)
+    ///   If the volume type is [Percent](VolumeUnit::Percent), the values are in the range [0.0, 1.0]
+    ///
+    /// # Errors
+    /// - `Error::NonFiniteValue`: If either value is NaN or infinite
+    /// - `Error::NegativeDose`: If the dose is negative
+    /// - `Error::NegativeVolume`: If the volume is negative
+    /// - `Error::PercentVolumeOutOfRange`: If a [Percent](VolumeUnit::Percent) volume
+    ///   exceeds 1.0
+    pub fn try_add(&mut self, d: f64, v: f64) -> crate::Result<()> {
+        self.validate_point(d, v)?;
+        self.is_sorted = false;
+        self.d.push(d);
+        self.v.push(v);
+        Ok(())
+    }
+
+    /// Adds multiple dose-volume data points to the DVH from slices, reporting why they
+    /// were rejected.
+    ///
+    /// Either all data points are added or none of them.
+    ///
+    /// # Parameters
+    /// - `d`: Slice of dose values (all must be finite and non-negative)
+    /// - `v`: Slice of volume values (all must be finite and non-negative)
+    ///
+    /// # Errors
+    /// - `Error::MismatchedLengthDoseVolumeData`: If the slices have different lengths
+    /// - Any error returned by [`Dvh::try_add`] for the first invalid data point
+    pub fn try_add_slice(&mut self, d: &[f64], v: &[f64]) -> crate::Result<()> {
         if d.len() != v.len() {
-            return false;
-        }
-        for x in d {
-            if *x < 0.0 {
-                return false;
-            }
+            return Err(Error::MismatchedLengthDoseVolumeData);
         }
-        for x in v {
-            if *x < 0.0 {
-                return false;
-            }
-            if self.volume_unit == VolumeUnit::Percent && *x > 1.0 {
-                return false;
-            }
+        for (&d, &v) in d.iter().zip(v.iter()) {
+            self.validate_point(d, v)?;
         }
 
         self.is_sorted = false;
         self.d.extend_from_slice(d);
         self.v.extend_from_slice(v);
-        true
+        Ok(())
     }
 
     /// Sorts the DVH data by dose values in ascending order.
     ///
     /// This method sorts both the dose and volume vectors together, maintaining
-    /// the correspondence between dose-volume pairs. If the data is already sorted,
+    /// the correspondence between dose-volume pairs. Data points with the same dose
+    /// are ordered by volume in descending order, as in a cumulative DVH, so the
+    /// result does not depend on the input order. If the data is already sorted,
     /// this is a no-op.
+    ///
+    /// This is the fast path and requires finite data, which [`Dvh::add`] and
+    /// [`Dvh::add_slice`] guarantee; it panics on NaN values. Use [`Dvh::sort_checked`]
+    /// for data that may not be finite.
     fn sort(&mut self) {
         if self.is_sorted {
             return;
         }
         let mut indices = (0..self.d.len()).collect::<Vec<_>>();
-        indices.sort_unstable_by(|&i, &j| self.d[i].partial_cmp(&self.d[j]).unwrap());
+        indices.sort_unstable_by(|&i, &j| {
+            self.d[i]
+                .partial_cmp(&self.d[j])
+                .unwrap()
+                .then_with(|| self.v[j].partial_cmp(&self.v[i]).unwrap())
+        });
 
         let d_sorted = indices.iter().map(|&i| self.d[i]).collect();
         let v_sorted = indices.iter().map(|&i| self.v[i]).collect();
@@ -208,9 +483,153 @@ impl Dvh {
         self.is_sorted = true;
     }
 
+    /// Checks whether a data point may be added to the DVH.
+    fn validate_point(&self, d: f64, v: f64) -> crate::Result<()> {
+        if !d.is_finite() || !v.is_finite() {
+            return Err(Error::NonFiniteValue);
+        }
+        if d < 0.0 {
+            return Err(Error::NegativeDose);
+        }
+        if v < 0.0 {
+            return Err(Error::NegativeVolume);
+        }
+        if self.volume_unit == VolumeUnit::Percent && v > 1.0 {
+            return Err(Error::PercentVolumeOutOfRange);
+        }
+        Ok(())
+    }
+
+    /// Checks whether the doses are actually sorted in ascending order.
+    ///
+    /// Unlike the `is_sorted` flag, this scans the data, so it also detects a flag that
+    /// became stale after the data was modified directly.
+    fn verify_sorted(&self) -> bool {
+        self.d.windows(2).all(|w| w[0] <= w[1])
+    }
+
+    /// Differentiates the sorted cumulative DVH into (dose, volume) bins.
+    ///
+    /// Each interval between consecutive data points yields a bin at its midpoint dose
+    /// with the volume difference of the interval. The volume remaining at the highest
+    /// dose point is attributed to that dose. The volumes sum to the volume at the
+    /// lowest dose point.
+    fn differential_bins(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let n = self.len();
+        self.d
+            .windows(2)
+            .zip(self.v.windows(2))
+            .map(|(d, v)| ((d[0] + d[1]) / 2.0, v[0] - v[1]))
+            .chain((n > 0).then(|| (self.d[n - 1], self.v[n - 1])))
+    }
+
+    /// Calculates the volume in dose bins of width `bin_width` starting at dose 0.
+    ///
+    /// The bins extend up to the bin containing the maximum dose. The volume of a bin is
+    /// the difference of the cumulative volumes at its edges, and the volume remaining at
+    /// the maximum dose is attributed to the last bin.
+    fn binned_volumes(&self, bin_width: f64) -> crate::Result<Vec<f64>> {
+        let num_bins = (self.max_dose() / bin_width).floor() as usize + 1;
+        let mut volumes = Vec::with_capacity(num_bins);
+        let mut lower = self.vx(0.0)?;
+        for k in 0..num_bins {
+            let upper = if k + 1 == num_bins {
+                0.0
+            } else {
+                self.vx((k + 1) as f64 * bin_width)?
+            };
+            volumes.push(lower - upper);
+            lower = upper;
+        }
+        Ok(volumes)
+    }
+
+    /// Creates a new DVH with the given data and the units and metadata of `self`.
+    fn with_data(&self, d: Vec<f64>, v: Vec<f64>, is_sorted: bool) -> Dvh {
+        Dvh {
+            d,
+            v,
+            is_sorted,
+            roi_type: self.roi_type,
+            reference_volume_cc: self.reference_volume_cc,
+            interp_mode: self.interp_mode,
+            percent_scale: self.percent_scale,
+            kind: self.kind,
+            prescription_dose: self.prescription_dose,
+            ..Dvh::new(self.dose_unit, self.volume_unit)
+        }
+    }
+
+    /// Resolves the total structure volume in cc.
+    ///
+    /// An explicit volume takes precedence over the reference volume of the DVH. For a
+    /// [Cc](VolumeUnit::Cc) DVH without either, the largest volume in the DVH is used.
+    pub(crate) fn resolve_total_volume(&self, explicit: Option<f64>) -> crate::Result<f64> {
+        let total = match explicit.or(self.reference_volume_cc) {
+            Some(total) => total,
+            None => match self.volume_unit {
+                VolumeUnit::Cc => self.v.iter().copied().fold(0.0, f64::max),
+                VolumeUnit::Percent => return Err(Error::VolumeUnitConversionRequired),
+            },
+        };
+        if total <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        Ok(total)
+    }
+
+    /// Combines the volumes of two DVHs on the union of their dose points.
+    ///
+    /// The volumes of both DVHs are obtained with [`Dvh::vx`] at each dose point and
+    /// combined with `f`. The result has the units and metadata of `self`.
+    fn combine_on_common_grid(
+        &self,
+        other: &Dvh,
+        f: impl Fn(f64, f64) -> f64,
+    ) -> crate::Result<Dvh> {
+        let mut d = self
+            .d
+            .iter()
+            .chain(other.d.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        d.sort_by(f64::total_cmp);
+        d.dedup();
+        let v = d
+            .iter()
+            .map(|&dose| Ok(f(self.vx(dose)?, other.vx(dose)?)))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(self.with_data(d, v, true))
+    }
+
+    /// Resamples the DVH onto the given dose points, sorted in ascending order.
+    ///
+    /// The volume at each dose point is obtained with [`Dvh::vx`].
+    fn resample_at(&self, doses: Vec<f64>) -> crate::Result<Dvh> {
+        let volumes = doses
+            .iter()
+            .map(|&dose| self.vx(dose))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(self.with_data(doses, volumes, true))
+    }
+
+    /// Returns the interpolation mode used by [`Dvh::dx`] and [`Dvh::vx`].
+    pub fn interp_mode(&self) -> InterpolationMode {
+        self.interp_mode
+    }
+
+    /// Sets the interpolation mode used by [`Dvh::dx`] and [`Dvh::vx`].
+    ///
+    /// # Parameters
+    /// - `mode`: The interpolation mode
+    pub fn set_interp_mode(&mut self, mode: InterpolationMode) {
+        self.interp_mode = mode;
+    }
+
     /// Calculates the minimum dose received by a given volume (Dx query).
     ///
-    /// This method performs linear interpolation to find the dose value at which
+    /// This method interpolates between the data points using the stored
+    /// [interpolation mode](Dvh::interp_mode) to find the dose value at which
     /// the specified volume is covered. The DVH must be sorted before calling this method.
     ///
     /// # Parameters
@@ -220,12 +639,34 @@ impl Dvh {
     /// The dose value at the specified volume
     ///
     /// # Errors
+    /// See [`Dvh::dx_with`].
+    pub fn dx(&self, volume: f64) -> crate::Result<f64> {
+        self.dx_with(volume, self.interp_mode)
+    }
+
+    /// Calculates the minimum dose received by a given volume (Dx query) using the
+    /// given interpolation mode.
+    ///
+    /// If the volume matches a stored volume within a few ULP, the stored dose is
+    /// returned exactly (the highest one if several data points share that volume).
+    /// The DVH must be sorted before calling this method. The segment containing the
+    /// volume is found with a binary search, which assumes that the volumes are
+    /// non-increasing, as in a cumulative DVH.
+    ///
+    /// # Parameters
+    /// - `volume`: The volume for which to find the dose (must be non-negative)
+    /// - `mode`: The interpolation mode used between data points
+    ///
+    /// # Returns
+    /// The dose value at the specified volume
+    ///
+    /// # Errors
     /// - `Error::NegativeVolume`: If the volume parameter is negative
     /// - `Error::DvhNoData`: If the DVH is empty
     /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
     /// - `Error::DvhUnsorted`: If the DVH is not sorted
     /// - `Error::DvhDxLogic`: If an internal logic error occurs
-    pub fn dx(&self, volume: f64) -> crate::Result<f64> {
+    pub fn dx_with(&self, volume: f64, mode: InterpolationMode) -> crate::Result<f64> {
         if volume < 0.0 {
             return Err(Error::NegativeVolume);
         }
@@ -238,30 +679,40 @@ impl Dvh {
         if !self.is_sorted {
             return Err(Error::DvhUnsorted);
         }
+        debug_assert!(self.verify_sorted(), "DVH is flagged as sorted but is not");
 
         let n = self.v.len();
-        let mut x0 = self.v[n - 1];
-        let mut y0 = self.d[n - 1];
-        if volume <= x0 {
-            return Ok(y0);
-        }
-        for (x1, y1) in self.v.iter().rev().zip(self.d.iter().rev()) {
-            if volume >= x0 && volume <= *x1 {
-                return Ok(linear_interpolation(volume, x0, *x1, y0, *y1));
-            }
-            x0 = *x1;
-            y0 = *y1;
+        if volume <= self.v[n - 1] {
+            return Ok(self.d[n - 1]);
         }
-        if volume > x0 {
-            return Ok(y0);
+        // The volumes of a cumulative DVH are non-increasing, so the points with a volume
+        // of at least `volume` come first.
+        let j = self.v.partition_point(|&v| v >= volume);
+        // Return the stored dose exactly instead of interpolating onto the data point
+        // (the highest one if several data points share that volume).
+        let mut i = j;
+        while i < n && ulps_eq(self.v[i], volume, EXACT_MATCH_ULPS) {
+            i += 1;
         }
-
-        Err(Error::DvhDxLogic)
+        if i > j {
+            return Ok(self.d[i - 1]);
+        }
+        if j > 0 && ulps_eq(self.v[j - 1], volume, EXACT_MATCH_ULPS) {
+            return Ok(self.d[j - 1]);
+        }
+        if j == 0 {
+            return Ok(self.d[0]);
+        }
+        if j == n {
+            return Err(Error::DvhDxLogic);
+        }
+        Ok(mode.interpolate_dose(volume, self.v[j], self.v[j - 1], self.d[j], self.d[j - 1]))
     }
 
     /// Calculates the volume receiving at least the specified dose (Vx query).
     ///
-    /// This method performs linear interpolation to find the volume value at the
+    /// This method interpolates between the data points using the stored
+    /// [interpolation mode](Dvh::interp_mode) to find the volume value at the
     /// specified dose level. The DVH must be sorted before calling this method.
     ///
     /// # Parameters
@@ -271,12 +722,32 @@ impl Dvh {
     /// The volume value at the specified dose
     ///
     /// # Errors
+    /// See [`Dvh::vx_with`].
+    pub fn vx(&self, dose: f64) -> crate::Result<f64> {
+        self.vx_with(dose, self.interp_mode)
+    }
+
+    /// Calculates the volume receiving at least the specified dose (Vx query) using the
+    /// given interpolation mode.
+    ///
+    /// If the dose matches a stored dose within a few ULP, the stored volume is
+    /// returned exactly (the first one if several data points share that dose).
+    /// The DVH must be sorted before calling this method.
+    ///
+    /// # Parameters
+    /// - `dose`: The dose level for which to find the volume (must be non-negative)
+    /// - `mode`: The interpolation mode used between data points
+    ///
+    /// # Returns
+    /// The volume value at the specified dose
+    ///
+    /// # Errors
     /// - `Error::NegativeDose`: If the dose parameter is negative
     /// - `Error::DvhNoData`: If the DVH is empty
     /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
     /// - `Error::DvhUnsorted`: If the DVH is not sorted
     /// - `Error::DvhVxLogic`: If an internal logic error occurs
-    pub fn vx(&self, dose: f64) -> crate::Result<f64> {
+    pub fn vx_with(&self, dose: f64, mode: InterpolationMode) -> crate::Result<f64> {
         if dose < 0.0 {
             return Err(Error::NegativeDose);
         }
@@ -289,24 +760,95 @@ impl Dvh {
         if !self.is_sorted {
             return Err(Error::DvhUnsorted);
         }
+        debug_assert!(self.verify_sorted(), "DVH is flagged as sorted but is not");
 
         let n = self.d.len();
-        let mut x0 = self.d[0];
-        let mut y0 = self.v[0];
-        if dose <= x0 {
-            return Ok(y0);
-        }
-        for (x1, y1) in self.d.iter().zip(self.v.iter()) {
-            if dose >= x0 && dose <= *x1 {
-                return Ok(linear_interpolation(dose, x0, *x1, y0, *y1));
-            }
-            x0 = *x1;
-            y0 = *y1;
+        if dose <= self.d[0] {
+            return Ok(self.v[0]);
+        }
+        // Index of the first data point with a dose of at least `dose`.
+        let k = self.d.partition_point(|&d| d < dose);
+        // Return the stored volume exactly instead of interpolating onto the data point
+        // (the first one if several data points share that dose).
+        let mut i = k;
+        while i > 0 && ulps_eq(self.d[i - 1], dose, EXACT_MATCH_ULPS) {
+            i -= 1;
         }
-        if dose > self.d[n - 1] {
+        if i < k {
+            return Ok(self.v[i]);
+        }
+        if k < n && ulps_eq(self.d[k], dose, EXACT_MATCH_ULPS) {
+            return Ok(self.v[k]);
+        }
+        if k == n {
             return Ok(self.v[n - 1]);
         }
-        Err(Error::DvhVxLogic)
+        if k == 0 {
+            return Err(Error::DvhVxLogic);
+        }
+        Ok(mode.interpolate_volume(dose, self.d[k - 1], self.d[k], self.v[k - 1], self.v[k]))
+    }
+
+    /// Calculates the dose for a batch of volumes sorted in descending order (batched Dx query).
+    ///
+    /// With the volume queries sorted in descending order, which is the natural order
+    /// along a cumulative DVH, the curve is walked only once to answer all queries in
+    /// O(n + m). The results are identical to calling [`Dvh::dx`] for each volume,
+    /// provided the volumes of the DVH are non-increasing.
+    ///
+    /// # Parameters
+    /// - `volumes`: The volumes for which to find the dose, sorted in descending order
+    ///   (all must be non-negative)
+    ///
+    /// # Returns
+    /// The dose values at the specified volumes, in query order
+    ///
+    /// # Errors
+    /// - `Error::UnsortedQueries`: If the volumes are not sorted in descending order
+    /// - `Error::NegativeVolume`: If any volume is negative
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    pub fn dx_sorted_queries(&self, volumes: &[f64]) -> crate::Result<Vec<f64>> {
+        if volumes.windows(2).any(|w| w[0] < w[1]) {
+            return Err(Error::UnsortedQueries);
+        }
+        if volumes.iter().any(|&v| v < 0.0) {
+            return Err(Error::NegativeVolume);
+        }
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+
+        let n = self.v.len();
+        let mut j = 0;
+        let mut doses = Vec::with_capacity(volumes.len());
+        for &volume in volumes {
+            while j + 1 < n && self.v[j + 1] >= volume {
+                j += 1;
+            }
+            let dose = if j + 1 == n {
+                self.d[n - 1]
+            } else if volume > self.v[j] || ulps_eq(volume, self.v[j], EXACT_MATCH_ULPS) {
+                self.d[j]
+            } else {
+                self.interp_mode.interpolate_dose(
+                    volume,
+                    self.v[j + 1],
+                    self.v[j],
+                    self.d[j + 1],
+                    self.d[j],
+                )
+            };
+            doses.push(dose);
+        }
+        Ok(doses)
     }
 
     /// Returns a reference to the slice of dose values in the DVH.
@@ -328,630 +870,5036 @@ impl Dvh {
     pub fn volumes(&self) -> &[f64] {
         &self.v
     }
-}
 
-impl DvhCheck for Dvh {
-    /// Validates the DVH data.
+    /// Calculates the minimum dose received by the hottest `cc` of the structure.
     ///
-    /// This method performs the following validation checks:
-    /// - Ensures that dose and volume vectors have the same length
-    /// - Verifies that all dose values are non-negative
-    /// - Verifies that all volume values are non-negative
-    /// - If the volume type is [Percent](VolumeUnit::Percent), verifies that all volume values are in the range [0.0, 1.0]
-    /// - Sorts the DVH data by dose in ascending order if not already sorted
+    /// For a cumulative DVH this is the dose at which the volume receiving at least
+    /// that dose equals `cc`, e.g. D0.035cc as a surrogate for the maximum dose.
+    /// For a [Percent](VolumeUnit::Percent) DVH, `cc` is first expressed as a fraction
+    /// of the total structure volume.
+    ///
+    /// # Parameters
+    /// - `cc`: The absolute volume (in cc) of the hottest region (must be non-negative)
+    /// - `structure_volume_cc`: The total volume of the structure in cc (must be positive).
+    ///   It is optional so that a [Percent](VolumeUnit::Percent) DVH can fall back to its
+    ///   reference volume when `None`. It is ignored for a [Cc](VolumeUnit::Cc) DVH, whose
+    ///   volumes are already absolute.
     ///
     /// # Returns
-    /// - `Ok(())` if all validations pass and data is successfully normalized
+    /// The dose covering the hottest `cc` of the structure
     ///
     /// # Errors
-    /// - `Error::MismatchedLengthDoseVolumeData`: If dose and volume vectors have different lengths
-    /// - `Error::NegativeDose`: If any dose value is negative
-    /// - `Error::NegativeVolume`: If any volume value is negative
-    /// - `Error::PercentVolumeOutOfRange`: If the volume type is [Percent](VolumeUnit::Percent) and any volume value exceeds 1.0
+    /// - `Error::NegativeVolume`: If `cc` is negative
+    /// - `Error::InvalidTotalVolume`: If the structure volume is not positive
+    /// - `Error::VolumeUnitConversionRequired`: If the structure volume of a
+    ///   [Percent](VolumeUnit::Percent) DVH is unknown
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn dose_to_outer_cc(
+        &self,
+        cc: f64,
+        structure_volume_cc: Option<f64>,
+    ) -> crate::Result<f64> {
+        if cc < 0.0 {
+            return Err(Error::NegativeVolume);
+        }
+        match self.volume_unit {
+            VolumeUnit::Percent => self.dx(cc / self.resolve_total_volume(structure_volume_cc)?),
+            VolumeUnit::Cc => self.dx(cc),
+        }
+    }
+
+    /// Returns a copy of the DVH with its volume unit relabelled to `volume_unit`.
     ///
-    /// # Example
-    /// ```
-    /// use dvh::{Dvh, DoseUnit, VolumeUnit, DvhCheck};
+    /// Relabelling the volume unit without converting the data silently corrupts
+    /// every volume value, so this method refuses any change of unit. Use
+    /// [`Dvh::to_absolute_volume`] or [`Dvh::to_percent_volume`] to convert the
+    /// data instead.
     ///
-    /// let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-    /// dvh.add(10.0, 0.8);
-    /// dvh.add(5.0, 1.0);
-    /// dvh.add(15.0, 0.5);
+    /// # Parameters
+    /// - `volume_unit`: The volume unit to relabel the DVH with
     ///
-    /// // Validate and sort the data
-    /// assert!(dvh.dvh_check().is_ok());
-    /// assert_eq!(dvh.doses(), vec![5.0, 10.0, 15.0]);
-    /// assert_eq!(dvh.volumes(), vec![1.0, 0.8, 0.5]);
-    /// ```
+    /// # Returns
+    /// A copy of the DVH if `volume_unit` equals the current volume unit
+    ///
+    /// # Errors
+    /// - `Error::WrongVolumeUnit`: If `volume_unit` differs from the current volume unit
+    pub fn reinterpret_volume_unit(&self, volume_unit: VolumeUnit) -> crate::Result<Dvh> {
+        if self.volume_unit != volume_unit {
+            return Err(Error::WrongVolumeUnit);
+        }
+        Ok(self.clone())
+    }
 
-    fn dvh_check(&mut self) -> crate::Result<()> {
-        if self.d.len() != self.v.len() {
-            return Err(Error::MismatchedLengthDoseVolumeData);
+    /// Converts a [Percent](VolumeUnit::Percent) DVH into a [Cc](VolumeUnit::Cc) DVH.
+    ///
+    /// The total volume used for the conversion is stored as the reference volume of
+    /// the new DVH.
+    ///
+    /// # Parameters
+    /// - `total_volume_cc`: The total volume of the structure in cc (must be positive).
+    ///   If `None`, the reference volume of the DVH is used.
+    ///
+    /// # Returns
+    /// A new DVH with every volume multiplied by the total volume
+    ///
+    /// # Errors
+    /// - `Error::WrongVolumeUnit`: If the DVH is not a [Percent](VolumeUnit::Percent) DVH
+    /// - `Error::InvalidTotalVolume`: If the total volume is not positive
+    /// - `Error::VolumeUnitConversionRequired`: If no total volume is given and the DVH
+    ///   has no reference volume
+    pub fn to_absolute_volume(&self, total_volume_cc: Option<f64>) -> crate::Result<Dvh> {
+        if self.volume_unit != VolumeUnit::Percent {
+            return Err(Error::WrongVolumeUnit);
         }
-        for x in &self.d {
-            if *x < 0.0 {
-                return Err(Error::NegativeDose);
-            }
+        let total_volume_cc = self.resolve_total_volume(total_volume_cc)?;
+        let mut dvh = self.clone();
+        dvh.volume_unit = VolumeUnit::Cc;
+        dvh.reference_volume_cc = Some(total_volume_cc);
+        dvh.v.iter_mut().for_each(|v| *v *= total_volume_cc);
+        Ok(dvh)
+    }
+
+    /// Converts a [Cc](VolumeUnit::Cc) DVH into a [Percent](VolumeUnit::Percent) DVH.
+    ///
+    /// The total volume used for the conversion is stored as the reference volume of
+    /// the new DVH.
+    ///
+    /// # Parameters
+    /// - `total_volume_cc`: The total volume of the structure in cc (must be positive).
+    ///   If `None`, the reference volume of the DVH is used, or the largest volume in
+    ///   the DVH if no reference volume is set.
+    ///
+    /// # Returns
+    /// A new DVH with every volume divided by the total volume
+    ///
+    /// # Errors
+    /// - `Error::WrongVolumeUnit`: If the DVH is not a [Cc](VolumeUnit::Cc) DVH
+    /// - `Error::InvalidTotalVolume`: If the total volume is not positive
+    /// - `Error::PercentVolumeOutOfRange`: If any volume exceeds the total volume
+    pub fn to_percent_volume(&self, total_volume_cc: Option<f64>) -> crate::Result<Dvh> {
+        if self.volume_unit != VolumeUnit::Cc {
+            return Err(Error::WrongVolumeUnit);
         }
-        for x in &self.v {
-            if *x < 0.0 {
-                return Err(Error::NegativeVolume);
+        let total_volume_cc = self.resolve_total_volume(total_volume_cc)?;
+        if self.v.iter().any(|&v| v > total_volume_cc) {
+            return Err(Error::PercentVolumeOutOfRange);
+        }
+        let mut dvh = self.clone();
+        dvh.volume_unit = VolumeUnit::Percent;
+        dvh.reference_volume_cc = Some(total_volume_cc);
+        dvh.v.iter_mut().for_each(|v| *v /= total_volume_cc);
+        Ok(dvh)
+    }
+
+    /// Resamples the DVH onto a logarithmically spaced dose grid.
+    ///
+    /// The dose points are spaced geometrically between `min_dose` and `max_dose`
+    /// (both inclusive), which gives more detail in the low-dose region than a uniform
+    /// grid with the same number of points. The volumes are obtained with [`Dvh::vx`].
+    ///
+    /// # Parameters
+    /// - `num_bins`: The number of dose points (must be at least 2)
+    /// - `min_dose`: The first dose point (must be positive)
+    /// - `max_dose`: The last dose point (must be larger than `min_dose`)
+    ///
+    /// # Returns
+    /// A new sorted DVH on the logarithmic dose grid
+    ///
+    /// # Errors
+    /// - `Error::InvalidDoseGrid`: If any of the grid parameters is invalid
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn resample_log(
+        &self,
+        num_bins: usize,
+        min_dose: f64,
+        max_dose: f64,
+    ) -> crate::Result<Dvh> {
+        if num_bins < 2 || min_dose <= 0.0 || max_dose <= min_dose {
+            return Err(Error::InvalidDoseGrid);
+        }
+        let ratio = (max_dose / min_dose).ln() / (num_bins - 1) as f64;
+        let mut doses = (0..num_bins)
+            .map(|i| min_dose * (ratio * i as f64).exp())
+            .collect::<Vec<_>>();
+        // Avoid rounding errors on the last grid point.
+        doses[num_bins - 1] = max_dose;
+        self.resample_at(doses)
+    }
+
+    /// Returns the DVH with its dose axis expressed as a percentage of the prescription.
+    ///
+    /// This is intended for overlaying DVHs of plans with different prescriptions. A
+    /// full [Dvh] is not returned because the dose is no longer expressed in a
+    /// [DoseUnit]. The data is returned in storage order.
+    ///
+    /// # Parameters
+    /// - `prescription`: The prescription dose in the dose unit of the DVH (must be positive).
+    ///   Ignored if the DVH has its own prescription dose.
+    ///
+    /// # Returns
+    /// A tuple with the doses in percent of the prescription and the unchanged volumes
+    ///
+    /// # Errors
+    /// - `Error::InvalidPrescription`: If `prescription` is not positive
+    pub fn normalized_overlay(&self, prescription: f64) -> crate::Result<(Vec<f64>, Vec<f64>)> {
+        let prescription = self.prescription_dose.unwrap_or(prescription);
+        if prescription <= 0.0 {
+            return Err(Error::InvalidPrescription);
+        }
+        let doses = self.d.iter().map(|d| d / prescription * 100.0).collect();
+        Ok((doses, self.v.clone()))
+    }
+
+    /// Calculates the volume-weighted mean dose within a dose window.
+    ///
+    /// The cumulative DVH is differentiated into bins between consecutive data points,
+    /// with the volume of a bin spread uniformly over its dose range (consistent with
+    /// linear interpolation of the cumulative DVH). Only the part of each bin that
+    /// falls within `[low, high]` contributes to the mean. The volume remaining at the
+    /// highest dose point is attributed to that dose. The DVH must be sorted before
+    /// calling this method.
+    ///
+    /// # Parameters
+    /// - `low`: The lower bound of the dose window (must be non-negative)
+    /// - `high`: The upper bound of the dose window (must be larger than `low`)
+    ///
+    /// # Returns
+    /// The mean dose of the volume receiving a dose within `[low, high]`
+    ///
+    /// # Errors
+    /// - `Error::NegativeDose`: If `low` is negative
+    /// - `Error::InvalidDoseWindow`: If `high` is not larger than `low`, or if no volume
+    ///   receives a dose within the window
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    pub fn mean_dose_between(&self, low: f64, high: f64) -> crate::Result<f64> {
+        if low < 0.0 {
+            return Err(Error::NegativeDose);
+        }
+        if high <= low {
+            return Err(Error::InvalidDoseWindow);
+        }
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+
+        let mut weighted_dose = 0.0;
+        let mut volume = 0.0;
+        for i in 0..self.len() - 1 {
+            let (d0, d1) = (self.d[i], self.d[i + 1]);
+            let dv = self.v[i] - self.v[i + 1];
+            if d1 == d0 {
+                if d0 >= low && d0 <= high {
+                    weighted_dose += dv * d0;
+                    volume += dv;
+                }
+                continue;
             }
-            if self.volume_unit == VolumeUnit::Percent && *x > 1.0 {
-                return Err(Error::PercentVolumeOutOfRange);
+            let a = d0.max(low);
+            let b = d1.min(high);
+            if b <= a {
+                continue;
             }
+            let dv_window = dv * (b - a) / (d1 - d0);
+            weighted_dose += dv_window * (a + b) / 2.0;
+            volume += dv_window;
+        }
+        let (d_last, v_last) = (self.d[self.len() - 1], self.v[self.len() - 1]);
+        if d_last >= low && d_last <= high {
+            weighted_dose += v_last * d_last;
+            volume += v_last;
+        }
+
+        if volume <= 0.0 {
+            return Err(Error::InvalidDoseWindow);
+        }
+        Ok(weighted_dose / volume)
+    }
+
+    /// Returns a new DVH containing the data points in the given index range.
+    ///
+    /// The units and metadata are preserved, and the new DVH is marked as sorted if
+    /// the source DVH is sorted.
+    ///
+    /// # Parameters
+    /// - `range`: The range of indices of the data points to keep
+    ///
+    /// # Returns
+    /// A new DVH with the data points in `range`
+    ///
+    /// # Errors
+    /// - `Error::IndexOutOfRange`: If `range` is decreasing or extends beyond the data points
+    pub fn slice(&self, range: std::ops::Range<usize>) -> crate::Result<Dvh> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(Error::IndexOutOfRange);
+        }
+        Ok(self.with_data(
+            self.d[range.clone()].to_vec(),
+            self.v[range].to_vec(),
+            self.is_sorted,
+        ))
+    }
+
+    /// Counts the stored data points with a dose strictly above the given dose.
+    ///
+    /// Only the stored data points are counted, the volume is not interpolated. This is
+    /// useful to detect under-sampled high-dose tails.
+    ///
+    /// # Parameters
+    /// - `dose`: The dose threshold
+    ///
+    /// # Returns
+    /// The number of data points with a dose larger than `dose`
+    pub fn points_above_dose(&self, dose: f64) -> usize {
+        self.d.iter().filter(|&&d| d > dose).count()
+    }
+
+    /// Counts the stored data points with a dose strictly below the given dose.
+    ///
+    /// Only the stored data points are counted, the volume is not interpolated.
+    ///
+    /// # Parameters
+    /// - `dose`: The dose threshold
+    ///
+    /// # Returns
+    /// The number of data points with a dose smaller than `dose`
+    pub fn points_below_dose(&self, dose: f64) -> usize {
+        self.d.iter().filter(|&&d| d < dose).count()
+    }
+
+    /// Converts the dose of each data point to the equivalent dose in 2 Gy fractions (EQD2).
+    ///
+    /// Each dose in the DVH is treated as the total dose delivered to the corresponding
+    /// voxels in `n_fractions` equal fractions, so the dose per fraction is `D / n` for
+    /// every data point rather than the prescribed dose per fraction. The EQD2 is computed
+    /// using the linear-quadratic model: `EQD2 = D * (D / n + α/β) / (2 + α/β)`, with
+    /// `α/β` expressed in the dose unit of the DVH. The prescription dose, if set, is
+    /// converted with the same formula. The volumes are left unchanged.
+    ///
+    /// # Parameters
+    /// - `alpha_beta`: The α/β ratio of the tissue, in the dose unit of the DVH
+    /// - `n_fractions`: The number of fractions in which the dose was delivered
+    ///
+    /// # Returns
+    /// A new DVH with the doses converted to EQD2
+    ///
+    /// # Errors
+    /// - `Error::InvalidRadiobiologyParameter`: If `alpha_beta` is not positive and finite
+    /// - `Error::InvalidFractionation`: If `n_fractions` is 0
+    pub fn to_eqd2_per_voxel(&self, alpha_beta: f64, n_fractions: u32) -> crate::Result<Dvh> {
+        if !(alpha_beta.is_finite() && alpha_beta > 0.0) {
+            return Err(Error::InvalidRadiobiologyParameter);
         }
+        if n_fractions == 0 {
+            return Err(Error::InvalidFractionation);
+        }
+        // In cGy, 2 Gy per fraction corresponds to 200 cGy.
+        let reference = match self.dose_unit {
+            DoseUnit::Gy => 2.0,
+            DoseUnit::CGy => 200.0,
+        };
+        let n = n_fractions as f64;
+        let eqd2 = |d: f64| d * (d / n + alpha_beta) / (reference + alpha_beta);
+        let d = self.d.iter().map(|&d| eqd2(d)).collect();
+        let mut dvh = self.with_data(d, self.v.clone(), self.is_sorted);
+        dvh.prescription_dose = self.prescription_dose.map(eqd2);
+        Ok(dvh)
+    }
+
+    /// Converts the dose axis to the equivalent dose in 2 Gy fractions (EQD2).
+    ///
+    /// Unlike [`Dvh::to_eqd2_per_voxel`], every data point is assumed to be delivered with
+    /// the same dose per fraction, typically the prescribed one. Each dose `D` is mapped
+    /// using the linear-quadratic model: `EQD2 = D * (d + α/β) / (2 + α/β)`, with `d` and
+    /// `α/β` expressed in the dose unit of the DVH. The prescription dose, if set, is
+    /// converted as well. The volumes are left unchanged.
+    ///
+    /// # Parameters
+    /// - `alpha_beta`: The α/β ratio of the tissue, in the dose unit of the DVH
+    /// - `dose_per_fraction`: The dose per fraction, in the dose unit of the DVH
+    ///
+    /// # Returns
+    /// A new DVH with the doses converted to EQD2
+    ///
+    /// # Errors
+    /// - `Error::InvalidRadiobiologyParameter`: If `alpha_beta` or `dose_per_fraction` is
+    ///   not positive and finite
+    pub fn to_eqd2(&self, alpha_beta: f64, dose_per_fraction: f64) -> crate::Result<Dvh> {
+        if !(alpha_beta.is_finite()
+            && alpha_beta > 0.0
+            && dose_per_fraction.is_finite()
+            && dose_per_fraction > 0.0)
         {
-            let is_sorted = self.is_sorted;
-            if !is_sorted {
-                self.sort();
-            }
+            return Err(Error::InvalidRadiobiologyParameter);
         }
-        Ok(())
+        // In cGy, 2 Gy per fraction corresponds to 200 cGy.
+        let reference = match self.dose_unit {
+            DoseUnit::Gy => 2.0,
+            DoseUnit::CGy => 200.0,
+        };
+        let factor = (dose_per_fraction + alpha_beta) / (reference + alpha_beta);
+        let d = self.d.iter().map(|&d| d * factor).collect();
+        let mut dvh = self.with_data(d, self.v.clone(), self.is_sorted);
+        dvh.prescription_dose = self.prescription_dose.map(|d| d * factor);
+        Ok(dvh)
     }
-}
 
-impl MaxDose for Dvh {
-    fn max_dose(&self) -> f64 {
-        if self.d.is_empty() {
-            return 0.0;
+    /// Returns the total structure volume of a cumulative [Cc](VolumeUnit::Cc) DVH.
+    ///
+    /// The structure volume is the volume of the dose 0 plateau. It is only returned if
+    /// the lowest dose point is at dose 0, or if the DVH is flat between its two lowest
+    /// dose points so that the plateau extrapolates to dose 0. The DVH must be sorted
+    /// before calling this method.
+    ///
+    /// # Returns
+    /// The structure volume in cc
+    ///
+    /// # Errors
+    /// - `Error::WrongVolumeUnit`: If the DVH does not have [Cc](VolumeUnit::Cc) volumes
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    /// - `Error::MissingZeroDosePoint`: If the DVH does not start at (or extrapolate to) dose 0
+    pub fn structure_volume(&self) -> crate::Result<f64> {
+        if self.volume_unit != VolumeUnit::Cc {
+            return Err(Error::WrongVolumeUnit);
         }
-        if self.is_sorted {
-            return *self.doses().last().unwrap();
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
         }
-        let a = *self.d.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-        if a >= 0.0 {
-            a
-        } else {
-            0.0
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
         }
+        let starts_at_zero = self.d[0] == 0.0;
+        let flat_start = self.len() > 1 && self.v[0] == self.v[1];
+        if !starts_at_zero && !flat_start {
+            return Err(Error::MissingZeroDosePoint);
+        }
+        Ok(self.v[0])
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use approx::assert_ulps_eq;
+    /// Returns the total volume of a [Cc](VolumeUnit::Cc) DVH.
+    ///
+    /// The total volume is the reference volume if set. Otherwise it is the largest
+    /// volume in the DVH, which for a cumulative DVH is the volume at dose 0. Unlike
+    /// [`Dvh::structure_volume`], the DVH does not need to be sorted or start at dose 0.
+    ///
+    /// # Returns
+    /// The total volume in cc
+    ///
+    /// # Errors
+    /// - `Error::VolumeUnitConversionRequired`: If the DVH has [Percent](VolumeUnit::Percent)
+    ///   volumes, for which the absolute total volume is unknown
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::InvalidTotalVolume`: If the total volume is not positive
+    pub fn total_volume(&self) -> crate::Result<f64> {
+        if self.volume_unit != VolumeUnit::Cc {
+            return Err(Error::VolumeUnitConversionRequired);
+        }
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        self.resolve_total_volume(None)
+    }
 
-    #[test]
-    fn test_linear_interpolation_normal() {
-        let result = linear_interpolation(5.0, 0.0, 10.0, 0.0, 100.0);
-        assert_eq!(result, 50.0);
+    /// Checks that all volumes of a [Cc](VolumeUnit::Cc) DVH are within a plausible range.
+    ///
+    /// Volumes in cc have no natural upper bound, but a negative or very large volume
+    /// signals corrupt data or a unit mix-up (e.g. percent data stored as cc).
+    ///
+    /// # Parameters
+    /// - `max_plausible_cc`: The largest plausible volume in cc
+    ///
+    /// # Errors
+    /// - `Error::WrongVolumeUnit`: If the DVH does not have [Cc](VolumeUnit::Cc) volumes
+    /// - `Error::ImplausibleVolume`: If a volume is outside `[0.0, max_plausible_cc]`,
+    ///   reporting the index and value of the first offending data point
+    pub fn validate_cc_range(&self, max_plausible_cc: f64) -> crate::Result<()> {
+        if self.volume_unit != VolumeUnit::Cc {
+            return Err(Error::WrongVolumeUnit);
+        }
+        match self
+            .v
+            .iter()
+            .position(|&v| !(0.0..=max_plausible_cc).contains(&v))
+        {
+            Some(index) => Err(Error::ImplausibleVolume {
+                index,
+                value: self.v[index],
+            }),
+            None => Ok(()),
+        }
     }
 
-    #[test]
-    fn test_linear_interpolation_same_x() {
-        let result = linear_interpolation(5.0, 10.0, 10.0, 20.0, 30.0);
-        assert_eq!(result, 20.0);
+    /// Checks whether the DVH matches a reference DVH on a fixed dose grid.
+    ///
+    /// Both DVHs are sampled with [`Dvh::vx`] at each dose of `grid` and the volumes are
+    /// compared. Because the comparison is done on the grid rather than on the stored
+    /// data points, two DVHs describing the same curve with a different sampling match.
+    /// This is intended for regression snapshots of computed DVHs.
+    ///
+    /// # Parameters
+    /// - `reference`: The reference DVH
+    /// - `grid`: The doses at which the DVHs are compared
+    /// - `tol`: The largest allowed absolute volume difference at a grid point
+    ///
+    /// # Returns
+    /// `true` if the units match and the volumes differ by at most `tol` at every grid
+    /// point, `false` otherwise or if either DVH cannot be queried
+    pub fn matches_reference(&self, reference: &Dvh, grid: &[f64], tol: f64) -> bool {
+        if self.dose_unit != reference.dose_unit || self.volume_unit != reference.volume_unit {
+            return false;
+        }
+        grid.iter()
+            .all(|&dose| match (self.vx(dose), reference.vx(dose)) {
+                (Ok(a), Ok(b)) => (a - b).abs() <= tol,
+                _ => false,
+            })
+    }
+
+    /// Creates a uniform dose grid from 0 up to the maximum dose of the DVH.
+    ///
+    /// The grid points are spaced `step` apart. The maximum dose is always the last
+    /// grid point, so the last interval is shorter than `step` if the maximum dose is
+    /// not a multiple of `step`.
+    ///
+    /// # Parameters
+    /// - `step`: The spacing between grid points (must be positive)
+    ///
+    /// # Returns
+    /// The dose grid in ascending order
+    ///
+    /// # Errors
+    /// - `Error::InvalidDoseGrid`: If `step` is not positive
+    /// - `Error::DvhNoData`: If the DVH is empty
+    pub fn uniform_grid(&self, step: f64) -> crate::Result<Vec<f64>> {
+        if step <= 0.0 || !step.is_finite() {
+            return Err(Error::InvalidDoseGrid);
+        }
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        let max_dose = self.max_dose();
+        // Compute each point from its index to avoid accumulating rounding errors, and
+        // merge a point that only falls short of the maximum dose by rounding.
+        let n = (max_dose / step * (1.0 - f64::EPSILON)).ceil() as usize;
+        let mut grid = (0..n).map(|i| i as f64 * step).collect::<Vec<_>>();
+        grid.push(max_dose);
+        Ok(grid)
+    }
+
+    /// Calculates the mean dose in a single pass over the cumulative DVH.
+    ///
+    /// The mean dose is the integral of the cumulative DVH over dose, computed with
+    /// the trapezoidal rule, plus the contribution of the plateau below the lowest dose
+    /// point. This is equivalent to [`Dvh::mean_dose`], which integrates the dose against
+    /// the differential DVH, but does not differentiate the DVH first.
+    /// For a [Percent](VolumeUnit::Percent) DVH the relative volumes are used directly,
+    /// for a [Cc](VolumeUnit::Cc) DVH the result is normalized by the volume at the lowest
+    /// dose point. The DVH must be sorted before calling this method.
+    ///
+    /// # Returns
+    /// The volume-weighted mean dose
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    /// - `Error::InvalidTotalVolume`: If the volume of a [Cc](VolumeUnit::Cc) DVH at the
+    ///   lowest dose point is not positive
+    pub fn mean_dose_streaming(&self) -> crate::Result<f64> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+
+        let mut integral = self.d[0] * self.v[0];
+        for i in 0..self.len() - 1 {
+            integral += (self.v[i] + self.v[i + 1]) / 2.0 * (self.d[i + 1] - self.d[i]);
+        }
+        match self.volume_unit {
+            VolumeUnit::Percent => Ok(integral),
+            VolumeUnit::Cc if self.v[0] > 0.0 => Ok(integral / self.v[0]),
+            VolumeUnit::Cc => Err(Error::InvalidTotalVolume),
+        }
+    }
+
+    /// Rounds the stored doses and volumes to a fixed number of decimals.
+    ///
+    /// This removes floating-point noise in the low bits, so that archived DVHs with the
+    /// same content are byte-for-byte identical. Rounding the doses can reorder data
+    /// points or make them coincide, so the DVH is sorted again afterwards and data
+    /// points that became identical are merged. Values that can't be scaled by the
+    /// rounding factor without overflowing, e.g. for more decimals than an `f64` can
+    /// represent, are left unchanged.
+    ///
+    /// # Parameters
+    /// - `dose_decimals`: The number of decimals to keep for the doses
+    /// - `volume_decimals`: The number of decimals to keep for the volumes
+    pub fn round_to(&mut self, dose_decimals: u32, volume_decimals: u32) {
+        let round = |x: &mut f64, decimals: u32| {
+            let factor = 10f64.powi(i32::try_from(decimals).unwrap_or(i32::MAX));
+            let scaled = *x * factor;
+            if scaled.is_finite() {
+                *x = scaled.round() / factor;
+            }
+        };
+        self.d.iter_mut().for_each(|d| round(d, dose_decimals));
+        self.v.iter_mut().for_each(|v| round(v, volume_decimals));
+        self.is_sorted = false;
+        self.sort();
+
+        let mut points = self
+            .d
+            .iter()
+            .copied()
+            .zip(self.v.iter().copied())
+            .collect::<Vec<_>>();
+        points.dedup();
+        (self.d, self.v) = points.into_iter().unzip();
+    }
+
+    /// Returns the conventional set of metrics to report for a structure type.
+    ///
+    /// Doses in the metrics are expressed in [Gy](DoseUnit::Gy).
+    ///
+    /// # Parameters
+    /// - `roi_type`: The clinical role of the structure
+    ///
+    /// # Returns
+    /// - [Target](RoiType::Target): D95, D2 and the homogeneity index
+    /// - [Oar](RoiType::Oar): mean dose, maximum dose, V20Gy and V30Gy
+    /// - [External](RoiType::External) and [Other](RoiType::Other): mean and maximum dose
+    pub fn default_metrics_for(roi_type: RoiType) -> Vec<Metric> {
+        match roi_type {
+            RoiType::Target => vec![Metric::Dx(0.95), Metric::Dx(0.02), Metric::HomogeneityIndex],
+            RoiType::Oar => vec![
+                Metric::MeanDose,
+                Metric::MaxDose,
+                Metric::Vx(20.0),
+                Metric::Vx(30.0),
+            ],
+            RoiType::External | RoiType::Other => vec![Metric::MeanDose, Metric::MaxDose],
+        }
+    }
+
+    /// Creates a cumulative [Cc](VolumeUnit::Cc) DVH from cumulative voxel counts.
+    ///
+    /// The volume at each dose is the number of voxels receiving at least that dose,
+    /// multiplied by the voxel volume.
+    ///
+    /// # Parameters
+    /// - `dose_bins`: The doses, sorted in ascending order
+    /// - `counts`: The number of voxels receiving at least the corresponding dose
+    /// - `voxel_volume_cc`: The volume of a voxel in cc
+    /// - `dose_type`: The unit type for dose measurements
+    ///
+    /// # Returns
+    /// A new sorted DVH
+    ///
+    /// # Errors
+    /// - `Error::MismatchedLengthDoseVolumeData`: If `dose_bins` and `counts` have
+    ///   different lengths
+    /// - `Error::NonFiniteValue`: If `voxel_volume_cc` or any dose is NaN or infinite
+    /// - `Error::InvalidVoxelVolume`: If `voxel_volume_cc` is not positive
+    /// - `Error::NegativeDose`: If any dose is negative
+    /// - `Error::DvhUnsorted`: If the doses are not sorted in ascending order
+    pub fn from_cumulative_counts(
+        dose_bins: &[f64],
+        counts: &[u64],
+        voxel_volume_cc: f64,
+        dose_type: DoseUnit,
+    ) -> crate::Result<Dvh> {
+        if dose_bins.len() != counts.len() {
+            return Err(Error::MismatchedLengthDoseVolumeData);
+        }
+        if !voxel_volume_cc.is_finite() || dose_bins.iter().any(|d| !d.is_finite()) {
+            return Err(Error::NonFiniteValue);
+        }
+        if voxel_volume_cc <= 0.0 {
+            return Err(Error::InvalidVoxelVolume);
+        }
+        if dose_bins.iter().any(|&d| d < 0.0) {
+            return Err(Error::NegativeDose);
+        }
+        if dose_bins.windows(2).any(|w| w[0] > w[1]) {
+            return Err(Error::DvhUnsorted);
+        }
+        Ok(Dvh {
+            d: dose_bins.to_vec(),
+            v: counts
+                .iter()
+                .map(|&count| count as f64 * voxel_volume_cc)
+                .collect(),
+            is_sorted: true,
+            ..Dvh::new(dose_type, VolumeUnit::Cc)
+        })
+    }
+
+    /// Returns the maximum dose in the DVH, or `None` if the DVH has no data.
+    ///
+    /// Unlike [`MaxDose::max_dose`], which returns 0.0 for an empty DVH, this
+    /// distinguishes "no data" from a genuine maximum dose of 0. Non-finite doses are
+    /// ignored as in [`MaxDose::max_dose`].
+    ///
+    /// # Returns
+    /// The maximum dose, or `None` if the DVH is empty or has no finite dose
+    pub fn max_dose_opt(&self) -> Option<f64> {
+        if self.d.iter().any(|d| d.is_finite()) {
+            Some(self.max_dose())
+        } else {
+            None
+        }
+    }
+
+    /// Converts the cumulative DVH into differential voxel counts for histogram export.
+    ///
+    /// The dose axis is divided into bins of width `bin_width` starting at dose 0, up to
+    /// the bin containing the maximum dose. The volume of each bin is the difference of
+    /// the cumulative volumes at its edges, and the volume remaining at the maximum dose
+    /// is attributed to the last bin. The volumes are converted to a number of voxels and
+    /// rounded to the nearest integer. A [Percent](VolumeUnit::Percent) DVH is converted
+    /// to cc using its reference volume.
+    ///
+    /// # Parameters
+    /// - `bin_width`: The width of the dose bins (must be positive)
+    /// - `voxel_volume_cc`: The volume of a voxel in cc (must be positive)
+    ///
+    /// # Returns
+    /// The `(bin_center, voxel_count)` pairs in ascending dose order
+    ///
+    /// # Errors
+    /// - `Error::InvalidDoseGrid`: If `bin_width` is not positive
+    /// - `Error::InvalidVoxelVolume`: If `voxel_volume_cc` is not positive
+    /// - `Error::VolumeUnitConversionRequired`: If a [Percent](VolumeUnit::Percent) DVH
+    ///   has no reference volume
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn differential_counts(
+        &self,
+        bin_width: f64,
+        voxel_volume_cc: f64,
+    ) -> crate::Result<Vec<(f64, u64)>> {
+        if bin_width <= 0.0 {
+            return Err(Error::InvalidDoseGrid);
+        }
+        if voxel_volume_cc <= 0.0 {
+            return Err(Error::InvalidVoxelVolume);
+        }
+        let scale = match self.volume_unit {
+            VolumeUnit::Cc => 1.0,
+            VolumeUnit::Percent => self.resolve_total_volume(None)?,
+        };
+        Ok(self
+            .binned_volumes(bin_width)?
+            .into_iter()
+            .enumerate()
+            .map(|(k, volume)| {
+                let voxels = (volume * scale / voxel_volume_cc).round().max(0.0);
+                ((k as f64 + 0.5) * bin_width, voxels as u64)
+            })
+            .collect())
+    }
+
+    /// Calculates the Shannon entropy of the differential dose distribution.
+    ///
+    /// The volume is binned as in [`Dvh::differential_counts`] and normalized to a
+    /// probability distribution over the bins. The entropy `-sum p_i * ln(p_i)` is
+    /// expressed in nats, with empty bins contributing zero. A distribution in a single
+    /// bin has an entropy of 0.
+    ///
+    /// # Parameters
+    /// - `bin_width`: The width of the dose bins (must be positive)
+    ///
+    /// # Returns
+    /// The entropy in nats
+    ///
+    /// # Errors
+    /// - `Error::InvalidDoseGrid`: If `bin_width` is not positive
+    /// - `Error::InvalidTotalVolume`: If the DVH has no volume
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn dose_entropy(&self, bin_width: f64) -> crate::Result<f64> {
+        if bin_width <= 0.0 {
+            return Err(Error::InvalidDoseGrid);
+        }
+        let volumes = self.binned_volumes(bin_width)?;
+        let total = volumes.iter().sum::<f64>();
+        if total <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        Ok(volumes
+            .iter()
+            .map(|&v| v / total)
+            .filter(|&p| p > 0.0)
+            .map(|p| -p * p.ln())
+            .sum())
+    }
+
+    /// Calculates the volume covered by several isodose levels of a prescription.
+    ///
+    /// Each isodose level is given as a percentage of the prescription dose, e.g.
+    /// `[90.0, 95.0, 100.0, 107.0]`. The volume is evaluated with [`Metric::Vx`], so it is
+    /// expressed as a fraction of the structure volume for both volume units.
+    ///
+    /// # Parameters
+    /// - `prescription`: The prescription dose in the dose unit of the DVH (must be positive).
+    ///   Ignored if the DVH has its own prescription dose.
+    /// - `percents`: The isodose levels in percent of the prescription
+    /// - `structure_volume_cc`: The total structure volume in cc, see [`Metric::evaluate`]
+    ///
+    /// # Returns
+    /// The `(percent_of_rx, volume)` pairs in the order of `percents`
+    ///
+    /// # Errors
+    /// - `Error::InvalidPrescription`: If `prescription` is not positive
+    /// - Any error returned by [`Metric::evaluate`]
+    pub fn coverage_levels(
+        &self,
+        prescription: f64,
+        percents: &[f64],
+        structure_volume_cc: Option<f64>,
+    ) -> crate::Result<Vec<(f64, f64)>> {
+        let prescription = self.prescription_dose.unwrap_or(prescription);
+        if prescription <= 0.0 {
+            return Err(Error::InvalidPrescription);
+        }
+        percents
+            .iter()
+            .map(|&percent| {
+                let dose = prescription * percent / 100.0;
+                let volume = Metric::Vx(dose).evaluate(self, structure_volume_cc)?;
+                Ok((percent, volume))
+            })
+            .collect()
+    }
+
+    /// Calculates the minimum dose received by a given volume (Dx query), verifying
+    /// that the data is actually sorted.
+    ///
+    /// [`Dvh::dx`] only checks the sorted flag of the DVH. This method also scans the
+    /// doses, which detects a flag that became stale after the data was modified.
+    ///
+    /// # Parameters
+    /// - `volume`: The volume for which to find the dose (must be non-negative)
+    ///
+    /// # Returns
+    /// The dose value at the specified volume
+    ///
+    /// # Errors
+    /// - `Error::DvhUnsorted`: If the DVH is not flagged as sorted or the doses are not
+    ///   in ascending order
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn dx_verified(&self, volume: f64) -> crate::Result<f64> {
+        if !self.verify_sorted() {
+            return Err(Error::DvhUnsorted);
+        }
+        self.dx(volume)
+    }
+
+    /// Calculates the volume receiving at least the specified dose (Vx query), verifying
+    /// that the data is actually sorted.
+    ///
+    /// See [`Dvh::dx_verified`].
+    ///
+    /// # Parameters
+    /// - `dose`: The dose level for which to find the volume (must be non-negative)
+    ///
+    /// # Returns
+    /// The volume value at the specified dose
+    ///
+    /// # Errors
+    /// - `Error::DvhUnsorted`: If the DVH is not flagged as sorted or the doses are not
+    ///   in ascending order
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn vx_verified(&self, dose: f64) -> crate::Result<f64> {
+        if !self.verify_sorted() {
+            return Err(Error::DvhUnsorted);
+        }
+        self.vx(dose)
+    }
+
+    /// Calculates the slope of the cumulative DVH between consecutive data points.
+    ///
+    /// The slope `dV/dD` of each interval is the finite difference of the volumes divided
+    /// by the finite difference of the doses. Intervals with zero dose width are skipped.
+    /// The DVH must be sorted before calling this method.
+    ///
+    /// # Returns
+    /// A tuple with the midpoint dose and the slope of each interval
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    pub fn gradient(&self) -> crate::Result<(Vec<f64>, Vec<f64>)> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+        Ok(self
+            .d
+            .windows(2)
+            .zip(self.v.windows(2))
+            .filter(|(d, _)| d[1] > d[0])
+            .map(|(d, v)| ((d[0] + d[1]) / 2.0, (v[1] - v[0]) / (d[1] - d[0])))
+            .unzip())
+    }
+
+    /// Calculates the volume-weighted mean dose.
+    ///
+    /// The cumulative DVH is differentiated into a differential histogram, with the
+    /// volume of each interval between consecutive data points placed at its midpoint
+    /// dose and the volume remaining at the highest dose point placed at that dose. The
+    /// mean dose is the integral of the dose against the differential volume. For a
+    /// [Percent](VolumeUnit::Percent) DVH the relative volumes are used directly, for a
+    /// [Cc](VolumeUnit::Cc) DVH the result is normalized by the total volume. The DVH must
+    /// be sorted before calling this method.
+    ///
+    /// # Returns
+    /// The volume-weighted mean dose
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    /// - `Error::InvalidTotalVolume`: If the total volume of a [Cc](VolumeUnit::Cc) DVH is
+    ///   not positive
+    pub fn mean_dose(&self) -> crate::Result<f64> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+
+        let (weighted_dose, total_volume) = self
+            .differential_bins()
+            .fold((0.0, 0.0), |(dose, volume), (d, v)| {
+                (dose + d * v, volume + v)
+            });
+        match self.volume_unit {
+            VolumeUnit::Percent => Ok(weighted_dose),
+            VolumeUnit::Cc if total_volume > 0.0 => Ok(weighted_dose / total_volume),
+            VolumeUnit::Cc => Err(Error::InvalidTotalVolume),
+        }
+    }
+
+    /// Clamps [Percent](VolumeUnit::Percent) volumes that are slightly out of range.
+    ///
+    /// Volumes in (1.0, 1.0 + [`PERCENT_CLAMP_TOLERANCE`]], e.g. 1.000001 due to rounding
+    /// in an export, are clamped down to 1.0 and volumes in
+    /// [-[`PERCENT_CLAMP_TOLERANCE`], 0.0) are clamped up to 0.0. Each volume is clamped
+    /// independently. Volumes further out of range are left unchanged, so that
+    /// [`DvhCheck::dvh_check`] still rejects clearly invalid data. A
+    /// [Cc](VolumeUnit::Cc) DVH is not modified.
+    pub fn clamp_percent_volumes(&mut self) {
+        if self.volume_unit != VolumeUnit::Percent {
+            return;
+        }
+        for v in self.v.iter_mut() {
+            if *v > 1.0 && *v <= 1.0 + PERCENT_CLAMP_TOLERANCE {
+                *v = 1.0;
+            } else if *v < 0.0 && *v >= -PERCENT_CLAMP_TOLERANCE {
+                *v = 0.0;
+            }
+        }
+    }
+
+    /// Deserializes a DVH, clamping slightly out-of-range percent volumes.
+    ///
+    /// Percent volumes within [`PERCENT_CLAMP_TOLERANCE`] outside of [0.0, 1.0] are
+    /// clamped with [`Dvh::clamp_percent_volumes`], so that they don't fail a later
+    /// [`DvhCheck::dvh_check`]. It can be used directly with a deserializer or as a
+    /// `#[serde(deserialize_with = "Dvh::deserialize_lenient")]` field attribute.
+    ///
+    /// # Parameters
+    /// - `deserializer`: The deserializer to read the DVH from
+    ///
+    /// # Returns
+    /// The deserialized DVH
+    ///
+    /// # Errors
+    /// Any error of the deserializer, or a custom error if a percent volume is further
+    /// than the tolerance out of range.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_lenient<'de, D>(deserializer: D) -> Result<Dvh, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::Deserialize;
+        use serde::de::Error as _;
+
+        let mut dvh = Dvh::deserialize(deserializer)?;
+        dvh.clamp_percent_volumes();
+        if dvh.volume_unit == VolumeUnit::Percent && dvh.v.iter().any(|v| !(0.0..=1.0).contains(v))
+        {
+            return Err(D::Error::custom(Error::PercentVolumeOutOfRange));
+        }
+        Ok(dvh)
+    }
+
+    /// Calculates the median dose (D50), the minimum dose covering half of the structure.
+    ///
+    /// For a [Percent](VolumeUnit::Percent) DVH this is [`Dvh::dx`] at 0.5, for a
+    /// [Cc](VolumeUnit::Cc) DVH [`Dvh::dx`] at half of the total volume, which is the
+    /// reference volume if set and the maximum volume otherwise. The DVH must be sorted
+    /// before calling this method.
+    ///
+    /// # Returns
+    /// The median dose
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::InvalidTotalVolume`: If the total volume of a [Cc](VolumeUnit::Cc) DVH is
+    ///   not positive
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn median_dose(&self) -> crate::Result<f64> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        let half = match self.volume_unit {
+            VolumeUnit::Percent => 0.5,
+            VolumeUnit::Cc => self.resolve_total_volume(None)? / 2.0,
+        };
+        self.dx(half)
+    }
+
+    /// Creates a DVH from an increasing cumulative DVH.
+    ///
+    /// Some systems export the increasing cumulative form, where the volume at a dose is
+    /// the volume receiving at most that dose. It is converted to the standard decreasing
+    /// cumulative form by subtracting each volume from the total volume.
+    ///
+    /// # Parameters
+    /// - `dose`: The doses, sorted in ascending order
+    /// - `vol`: The volumes receiving at most the corresponding dose, non-decreasing
+    /// - `total`: The total volume of the structure, in `volume_type` (e.g. 1.0 for a
+    ///   [Percent](VolumeUnit::Percent) DVH)
+    /// - `dose_type`: The unit type for dose measurements
+    /// - `volume_type`: The unit type for volume measurements
+    ///
+    /// # Returns
+    /// A new sorted DVH in the standard decreasing cumulative form
+    ///
+    /// # Errors
+    /// - `Error::MismatchedLengthDoseVolumeData`: If `dose` and `vol` have different lengths
+    /// - `Error::InvalidTotalVolume`: If `total` is not positive
+    /// - `Error::DvhUnsorted`: If the doses are not sorted in ascending order
+    /// - `Error::NonMonotonicVolume`: If the volumes decrease
+    /// - Any error returned by [`DvhCheck::dvh_check`], e.g. if a volume exceeds `total`
+    pub fn import_increasing_cumulative(
+        dose: &[f64],
+        vol: &[f64],
+        total: f64,
+        dose_type: DoseUnit,
+        volume_type: VolumeUnit,
+    ) -> crate::Result<Dvh> {
+        if dose.len() != vol.len() {
+            return Err(Error::MismatchedLengthDoseVolumeData);
+        }
+        if total <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        if dose.windows(2).any(|w| w[0] > w[1]) {
+            return Err(Error::DvhUnsorted);
+        }
+        if vol.windows(2).any(|w| w[0] > w[1]) {
+            return Err(Error::NonMonotonicVolume);
+        }
+        let mut dvh = Dvh {
+            d: dose.to_vec(),
+            v: vol.iter().map(|&v| total - v).collect(),
+            ..Dvh::new(dose_type, volume_type)
+        };
+        dvh.dvh_check()?;
+        Ok(dvh)
+    }
+
+    /// Finds the dose at which the cumulative DVH crosses a coverage level.
+    ///
+    /// The coverage is expressed as a fraction of the structure volume. For a
+    /// [Cc](VolumeUnit::Cc) DVH it is converted to cc using the reference volume, or the
+    /// largest volume if no reference volume is set. The DVH must be sorted before
+    /// calling this method.
+    ///
+    /// # Parameters
+    /// - `coverage_fraction`: The coverage level in the range [0.0, 1.0]
+    ///
+    /// # Returns
+    /// A tuple with the crossing dose and `true` if it was interpolated between data
+    /// points, or `false` if the coverage level is a stored data point. A coverage level
+    /// outside the range of the stored volumes is clamped by [`Dvh::dx`] to the dose of
+    /// the first or last data point and is also reported as not interpolated.
+    ///
+    /// # Errors
+    /// - `Error::PercentVolumeOutOfRange`: If `coverage_fraction` is outside [0.0, 1.0]
+    /// - `Error::InvalidTotalVolume`: If the total volume of a [Cc](VolumeUnit::Cc) DVH is
+    ///   not positive
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn coverage_crossing(&self, coverage_fraction: f64) -> crate::Result<(f64, bool)> {
+        if !(0.0..=1.0).contains(&coverage_fraction) {
+            return Err(Error::PercentVolumeOutOfRange);
+        }
+        let volume = match self.volume_unit {
+            VolumeUnit::Percent => coverage_fraction,
+            VolumeUnit::Cc => coverage_fraction * self.resolve_total_volume(None)?,
+        };
+        let dose = self.dx(volume)?;
+        let exact = self.v.iter().any(|&v| ulps_eq(v, volume, EXACT_MATCH_ULPS));
+        // dx succeeded, so the DVH is sorted with non-increasing volumes.
+        let in_range = volume < self.v[0] && volume > self.v[self.len() - 1];
+        Ok((dose, in_range && !exact))
+    }
+
+    /// Calculates the volume receiving at least an absolute dose.
+    ///
+    /// The dose is expressed in the [DoseUnit] of the DVH, e.g. V20Gy of a
+    /// [Gy](DoseUnit::Gy) DVH is `vx_abs(20.0)` and of a [CGy](DoseUnit::CGy) DVH
+    /// `vx_abs(2000.0)`. The volume is returned in the [VolumeUnit] of the DVH. This is
+    /// the same query as [`Dvh::vx`].
+    ///
+    /// # Parameters
+    /// - `dose`: The absolute dose threshold (must be non-negative)
+    ///
+    /// # Returns
+    /// The volume receiving at least `dose`
+    ///
+    /// # Errors
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn vx_abs(&self, dose: f64) -> crate::Result<f64> {
+        self.vx(dose)
+    }
+
+    /// Calculates the fraction of the structure volume receiving at least an absolute dose.
+    ///
+    /// Unlike [`Dvh::vx_abs`], the result is a fraction in the range [0.0, 1.0] for both
+    /// volume units. For a [Cc](VolumeUnit::Cc) DVH the volume is divided by the reference
+    /// volume, or by the largest volume if no reference volume is set.
+    ///
+    /// # Parameters
+    /// - `dose`: The absolute dose threshold in the [DoseUnit] of the DVH (must be non-negative)
+    ///
+    /// # Returns
+    /// The fraction of the structure volume receiving at least `dose`
+    ///
+    /// # Errors
+    /// - `Error::VolumeUnitConversionRequired`: If the total volume cannot be determined
+    /// - `Error::InvalidTotalVolume`: If the total volume is not positive
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn vx_percent(&self, dose: f64) -> crate::Result<f64> {
+        match self.volume_unit {
+            VolumeUnit::Percent => self.vx(dose),
+            VolumeUnit::Cc => Ok(self.vx(dose)? / self.resolve_total_volume(None)?),
+        }
+    }
+
+    /// Scales the doses so that the dose at a reference volume equals a target dose.
+    ///
+    /// This renormalizes a plan, e.g. so that D50 equals the prescription. The current
+    /// dose at `reference_volume` is calculated with [`Dvh::dx`] and all doses are
+    /// multiplied by `target_dose / current`.
+    ///
+    /// # Parameters
+    /// - `reference_volume`: The reference volume, in the [VolumeUnit] of the DVH
+    /// - `target_dose`: The dose required at the reference volume (must be positive)
+    ///
+    /// # Returns
+    /// A new DVH with the scaled doses
+    ///
+    /// # Errors
+    /// - `Error::InvalidPrescription`: If `target_dose` is not positive
+    /// - `Error::InvalidReferenceDose`: If the current dose at `reference_volume` is 0
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn renormalize_to(&self, reference_volume: f64, target_dose: f64) -> crate::Result<Dvh> {
+        if target_dose <= 0.0 {
+            return Err(Error::InvalidPrescription);
+        }
+        let current = self.dx(reference_volume)?;
+        if current <= 0.0 {
+            return Err(Error::InvalidReferenceDose);
+        }
+        let scale = target_dose / current;
+        let d = self.d.iter().map(|&d| d * scale).collect();
+        Ok(self.with_data(d, self.v.clone(), self.is_sorted))
+    }
+
+    /// Calculates the generalized equivalent uniform dose (gEUD).
+    ///
+    /// The gEUD is `(sum v_i * d_i^a / sum v_i)^(1/a)` over the bins of the differential
+    /// DVH derived from the cumulative data, with the volume of each interval between
+    /// consecutive data points placed at its midpoint dose. For `a == 0.0` the limit, the
+    /// volume-weighted geometric mean dose, is returned. For `a -> inf` the gEUD approaches
+    /// the maximum dose and for `a -> -inf` the minimum dose of the differential DVH.
+    /// `a = 1` gives the mean dose. If a bin at dose 0 has volume, the gEUD is 0 for
+    /// `a <= 0`. The DVH must be sorted before calling this method.
+    ///
+    /// # Parameters
+    /// - `a`: The tissue-specific volume-effect parameter
+    ///
+    /// # Returns
+    /// The gEUD in the dose unit of the DVH
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    /// - `Error::InvalidTotalVolume`: If the total volume is not positive
+    pub fn geud(&self, a: f64) -> crate::Result<f64> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+
+        let bins = self.differential_bins().filter(|&(_, v)| v > 0.0);
+        let (sum, total_volume) = if a == 0.0 {
+            bins.fold((0.0, 0.0), |(sum, total), (d, v)| {
+                (sum + v * d.ln(), total + v)
+            })
+        } else {
+            bins.fold((0.0, 0.0), |(sum, total), (d, v)| {
+                (sum + v * d.powf(a), total + v)
+            })
+        };
+        if total_volume <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        if a == 0.0 {
+            Ok((sum / total_volume).exp())
+        } else {
+            Ok((sum / total_volume).powf(1.0 / a))
+        }
+    }
+
+    /// Converts the cumulative DVH into a differential DVH.
+    ///
+    /// Each interval between consecutive data points becomes a bin at the midpoint dose,
+    /// with the volume difference of the interval as its volume. The volume remaining at
+    /// the highest dose point is kept as a last bin at that dose, so the volumes of the
+    /// differential DVH sum to the total volume of the cumulative DVH. The units and
+    /// metadata are preserved and the kind of the new DVH is
+    /// [Differential](DvhKind::Differential).
+    ///
+    /// # Returns
+    /// A new sorted differential DVH
+    ///
+    /// # Errors
+    /// - `Error::WrongDvhKind`: If the DVH is not a [Cumulative](DvhKind::Cumulative) DVH
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    pub fn to_differential(&self) -> crate::Result<Dvh> {
+        if self.kind != DvhKind::Cumulative {
+            return Err(Error::WrongDvhKind);
+        }
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+        let (d, v) = self.differential_bins().unzip();
+        let mut dvh = self.with_data(d, v, true);
+        dvh.kind = DvhKind::Differential;
+        Ok(dvh)
+    }
+
+    /// Converts the differential DVH into a cumulative DVH.
+    ///
+    /// The differential volumes are summed from the highest dose downward, so the volume
+    /// at each dose of the cumulative DVH is the total volume of the bins at that dose or
+    /// above. That volume is reported at the lower edge of the bin: the bins are assumed
+    /// to be centred on contiguous dose intervals, with the first interval as wide as the
+    /// spacing between the first two bins. This inverts [`Dvh::to_differential`] for a
+    /// cumulative DVH on a uniform dose grid. The units and metadata are preserved and
+    /// the kind of the new DVH is [Cumulative](DvhKind::Cumulative).
+    ///
+    /// # Returns
+    /// A new sorted cumulative DVH with non-increasing volumes
+    ///
+    /// # Errors
+    /// - `Error::WrongDvhKind`: If the DVH is not a [Differential](DvhKind::Differential) DVH
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    /// - `Error::NegativeVolume`: If any differential volume is negative
+    pub fn to_cumulative(&self) -> crate::Result<Dvh> {
+        if self.kind != DvhKind::Differential {
+            return Err(Error::WrongDvhKind);
+        }
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+        if self.v.iter().any(|&v| v < 0.0) {
+            return Err(Error::NegativeVolume);
+        }
+        let mut v = self.v.clone();
+        for i in (0..v.len() - 1).rev() {
+            v[i] += v[i + 1];
+        }
+        // The upper edge of a bin is the lower edge of the next one.
+        let mut d = Vec::with_capacity(self.len());
+        let mut edge = self.d[0] - (self.d[1] - self.d[0]) / 2.0;
+        for i in 0..self.len() {
+            d.push(edge.max(0.0));
+            if i + 1 < self.len() {
+                edge = (2.0 * self.d[i] - edge).clamp(self.d[i], self.d[i + 1]);
+            }
+        }
+        let mut dvh = self.with_data(d, v, true);
+        dvh.kind = DvhKind::Cumulative;
+        Ok(dvh)
+    }
+
+    /// Converts the DVH to another dose unit.
+    ///
+    /// The doses, and the prescription dose if set, are multiplied by 100 to convert
+    /// from [Gy](DoseUnit::Gy) to [CGy](DoseUnit::CGy) and by 0.01 to convert from
+    /// [CGy](DoseUnit::CGy) to [Gy](DoseUnit::Gy). The volumes are left unchanged.
+    /// Converting to the dose unit of the DVH returns a clone.
+    ///
+    /// # Parameters
+    /// - `target`: The dose unit to convert to
+    ///
+    /// # Returns
+    /// A new DVH with the doses in `target`
+    pub fn to_dose_unit(&self, target: DoseUnit) -> Dvh {
+        let factor = match (self.dose_unit, target) {
+            (DoseUnit::Gy, DoseUnit::CGy) => 100.0,
+            (DoseUnit::CGy, DoseUnit::Gy) => 0.01,
+            _ => return self.clone(),
+        };
+        let mut dvh = self.clone();
+        dvh.dose_unit = target;
+        dvh.d.iter_mut().for_each(|d| *d *= factor);
+        dvh.prescription_dose = dvh.prescription_dose.map(|d| d * factor);
+        dvh
+    }
+
+    /// Renders the DVH curve as a small inline SVG sparkline.
+    ///
+    /// The curve is drawn as a single `<polyline>` with one point per data point, scaled
+    /// so that the dose range [0, max dose] spans the width and the volume range
+    /// [0, max volume] spans the height of the viewport. No axes are drawn.
+    ///
+    /// # Parameters
+    /// - `width`: The width of the viewport in pixels
+    /// - `height`: The height of the viewport in pixels
+    ///
+    /// # Returns
+    /// An `<svg>` element as a string
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    #[cfg(feature = "svg")]
+    pub fn to_svg(&self, width: u32, height: u32) -> crate::Result<String> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+        let (width, height) = (width as f64, height as f64);
+        let max_dose = self.max_dose();
+        let max_volume = self.v.iter().copied().fold(0.0, f64::max);
+        let scale = |value: f64, max: f64| if max > 0.0 { value / max } else { 0.0 };
+        let points = self
+            .d
+            .iter()
+            .zip(self.v.iter())
+            .map(|(&d, &v)| {
+                let x = scale(d, max_dose) * width;
+                let y = (1.0 - scale(v, max_volume)) * height;
+                format!("{x:.2},{y:.2}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\"><polyline fill=\"none\" stroke=\"black\" \
+             points=\"{points}\"/></svg>"
+        ))
+    }
+
+    /// Converts the DVH to another volume unit using the given total structure volume.
+    ///
+    /// This dispatches to [`Dvh::to_absolute_volume`] or [`Dvh::to_percent_volume`]
+    /// depending on `target`. Converting to the volume unit of the DVH returns a clone.
+    ///
+    /// # Parameters
+    /// - `target`: The volume unit to convert to
+    /// - `total_volume_cc`: The total volume of the structure in cc (must be positive)
+    ///
+    /// # Returns
+    /// A new DVH with the volumes in `target`
+    ///
+    /// # Errors
+    /// - `Error::InvalidTotalVolume`: If `total_volume_cc` is not positive
+    /// - `Error::PercentVolumeOutOfRange`: If converting to [Percent](VolumeUnit::Percent)
+    ///   and any volume exceeds `total_volume_cc`
+    pub fn to_volume_unit(&self, target: VolumeUnit, total_volume_cc: f64) -> crate::Result<Dvh> {
+        if total_volume_cc.is_nan() || total_volume_cc <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        match (self.volume_unit, target) {
+            (VolumeUnit::Percent, VolumeUnit::Cc) => self.to_absolute_volume(Some(total_volume_cc)),
+            (VolumeUnit::Cc, VolumeUnit::Percent) => self.to_percent_volume(Some(total_volume_cc)),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Computes the fraction of the structure volume in each of a set of dose bands.
+    ///
+    /// Band `i` spans the doses `[edges[i], edges[i + 1])` and its volume is the
+    /// difference `vx(edges[i]) - vx(edges[i + 1])`, normalized to the total structure
+    /// volume. The fractions sum to the fraction of the volume covered by the outer edges.
+    ///
+    /// # Parameters
+    /// - `edges`: The `n + 1` band boundaries, sorted in ascending order
+    ///
+    /// # Returns
+    /// The `n` volume fractions [0.0, 1.0], one per band
+    ///
+    /// # Errors
+    /// - `Error::WrongDvhKind`: If the DVH is not a cumulative DVH
+    /// - `Error::InvalidDoseGrid`: If fewer than 2 edges are given
+    /// - `Error::UnsortedQueries`: If the edges are not sorted in ascending order
+    /// - Any error returned by [`Dvh::vx`] or by resolving the total structure volume
+    ///   of a [Cc](VolumeUnit::Cc) DVH
+    pub fn banded_volume_fractions(&self, edges: &[f64]) -> crate::Result<Vec<f64>> {
+        if self.kind != DvhKind::Cumulative {
+            return Err(Error::WrongDvhKind);
+        }
+        if edges.len() < 2 {
+            return Err(Error::InvalidDoseGrid);
+        }
+        if edges.windows(2).any(|w| w[0] > w[1]) {
+            return Err(Error::UnsortedQueries);
+        }
+        let total = match self.volume_unit {
+            VolumeUnit::Percent => 1.0,
+            VolumeUnit::Cc => self.resolve_total_volume(None)?,
+        };
+        let volumes = edges
+            .iter()
+            .map(|&dose| self.vx(dose))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(volumes.windows(2).map(|w| (w[0] - w[1]) / total).collect())
+    }
+
+    /// Resamples the DVH onto a uniform dose grid.
+    ///
+    /// The new dose points are 0, `bin_width`, 2 * `bin_width`, ... up to and including
+    /// the maximum dose (see [`Dvh::uniform_grid`]). The volumes are obtained with
+    /// [`Dvh::vx`], so DVHs of different structures resampled with the same bin width
+    /// can be compared point by point.
+    ///
+    /// # Parameters
+    /// - `bin_width`: The spacing between dose points (must be positive)
+    ///
+    /// # Returns
+    /// A new sorted DVH on the uniform dose grid
+    ///
+    /// # Errors
+    /// - `Error::InvalidBinWidth`: If `bin_width` is not positive or not finite
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn resample(&self, bin_width: f64) -> crate::Result<Dvh> {
+        if bin_width <= 0.0 || !bin_width.is_finite() {
+            return Err(Error::InvalidBinWidth);
+        }
+        let grid = self.uniform_grid(bin_width)?;
+        self.resample_at(grid)
+    }
+
+    /// Combines the cumulative DVHs of two structures into the DVH of their union.
+    ///
+    /// Both DVHs are resampled with [`Dvh::vx`] onto the union of their dose points and
+    /// the volumes are summed per dose point, e.g. to evaluate the left and right lung
+    /// as a single organ. The structures are assumed not to overlap. The reference
+    /// volume of the result is the sum of both reference volumes if both are set.
+    ///
+    /// # Parameters
+    /// - `other`: The DVH of the other structure
+    ///
+    /// # Returns
+    /// A new sorted DVH of the combined structure
+    ///
+    /// # Errors
+    /// - `Error::IncompatibleDvhUnits`: If the DVHs have different dose or volume units
+    /// - `Error::WrongVolumeUnit`: If the DVHs are not [Cc](VolumeUnit::Cc) DVHs
+    /// - `Error::WrongDvhKind`: If either DVH is not a cumulative DVH
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn merge(&self, other: &Dvh) -> crate::Result<Dvh> {
+        if self.dose_unit != other.dose_unit || self.volume_unit != other.volume_unit {
+            return Err(Error::IncompatibleDvhUnits);
+        }
+        if self.volume_unit != VolumeUnit::Cc {
+            return Err(Error::WrongVolumeUnit);
+        }
+        if self.kind != DvhKind::Cumulative || other.kind != DvhKind::Cumulative {
+            return Err(Error::WrongDvhKind);
+        }
+        let mut dvh = self.combine_on_common_grid(other, |a, b| a + b)?;
+        dvh.reference_volume_cc = self
+            .reference_volume_cc
+            .zip(other.reference_volume_cc)
+            .map(|(a, b)| a + b);
+        Ok(dvh)
+    }
+
+    /// Checks whether the DVH carries any real coverage.
+    ///
+    /// Structures with fewer than 2 data points or without any positive volume are
+    /// usually empty or junk structures and can be filtered out before computing metrics.
+    ///
+    /// # Returns
+    /// `true` if the DVH has at least 2 data points and a positive volume somewhere
+    pub fn has_coverage(&self) -> bool {
+        self.len() >= 2 && self.v.iter().any(|&v| v > 0.0)
+    }
+
+    /// Formats a dose value with the dose unit of the DVH, e.g. "60.0 Gy".
+    ///
+    /// Doses in [Gy](DoseUnit::Gy) are shown with 1 decimal and doses in
+    /// [CGy](DoseUnit::CGy) without decimals.
+    ///
+    /// # Parameters
+    /// - `d`: The dose value in the dose unit of the DVH
+    ///
+    /// # Returns
+    /// The formatted dose
+    pub fn format_dose(&self, d: f64) -> String {
+        match self.dose_unit {
+            DoseUnit::Gy => format!("{d:.1} {}", self.dose_unit),
+            DoseUnit::CGy => format!("{d:.0} {}", self.dose_unit),
+        }
+    }
+
+    /// Formats a volume value with the volume unit of the DVH, e.g. "95.0 %".
+    ///
+    /// [Percent](VolumeUnit::Percent) volumes are stored as fractions [0.0, 1.0] and are
+    /// multiplied by 100 for display. Volumes are shown with 1 decimal.
+    ///
+    /// # Parameters
+    /// - `v`: The volume value in the volume unit of the DVH
+    ///
+    /// # Returns
+    /// The formatted volume
+    pub fn format_volume(&self, v: f64) -> String {
+        match self.volume_unit {
+            VolumeUnit::Percent => format!("{:.1} {}", v * 100.0, self.volume_unit),
+            VolumeUnit::Cc => format!("{v:.1} {}", self.volume_unit),
+        }
+    }
+
+    /// Returns a sorted copy of the DVH, leaving the original untouched.
+    ///
+    /// This allows querying the DVH with [`Dvh::dx`] or [`Dvh::vx`] through a shared
+    /// reference. Data points are ordered as by [`DvhCheck::dvh_check`].
+    ///
+    /// # Returns
+    /// A sorted clone of the DVH
+    pub fn sorted(&self) -> Dvh {
+        let mut dvh = self.clone();
+        dvh.sort();
+        dvh
+    }
+
+    /// Checks a target DVH against common prescription QA rules.
+    ///
+    /// A warning is produced if the maximum dose exceeds `max_hot_pct` percent of the
+    /// prescription, or if D95 falls below `min_cold_pct` percent of the prescription.
+    /// D95 is evaluated with [`Metric::Dx`], so it refers to 95% of the structure volume
+    /// for both volume units.
+    ///
+    /// # Parameters
+    /// - `prescription`: The prescription dose in the dose unit of the DVH (must be positive).
+    ///   Ignored if the DVH has its own prescription dose.
+    /// - `max_hot_pct`: The highest acceptable maximum dose in percent of the prescription,
+    ///   e.g. 120.0
+    /// - `min_cold_pct`: The lowest acceptable D95 in percent of the prescription, e.g. 95.0
+    ///
+    /// # Returns
+    /// Human-readable warnings for the breached rules, empty if the DVH passes all rules
+    ///
+    /// # Errors
+    /// - `Error::InvalidPrescription`: If `prescription` is not positive
+    /// - Any error returned by [`Metric::evaluate`]
+    pub fn check_prescription_sanity(
+        &self,
+        prescription: f64,
+        max_hot_pct: f64,
+        min_cold_pct: f64,
+    ) -> crate::Result<Vec<String>> {
+        let prescription = self.prescription_dose.unwrap_or(prescription);
+        if prescription <= 0.0 {
+            return Err(Error::InvalidPrescription);
+        }
+        let mut warnings = Vec::new();
+        let max_dose = self.max_dose();
+        if max_dose > prescription * max_hot_pct / 100.0 {
+            warnings.push(format!(
+                "Maximum dose {} exceeds {max_hot_pct}% of the prescription ({:.1}%).",
+                self.format_dose(max_dose),
+                max_dose / prescription * 100.0
+            ));
+        }
+        let d95 = Metric::Dx(0.95).evaluate(self, None)?;
+        if d95 < prescription * min_cold_pct / 100.0 {
+            warnings.push(format!(
+                "D95 {} is below {min_cold_pct}% of the prescription ({:.1}%).",
+                self.format_dose(d95),
+                d95 / prescription * 100.0
+            ));
+        }
+        Ok(warnings)
+    }
+
+    /// Returns an iterator over the (dose, volume) pairs in storage order.
+    ///
+    /// # Returns
+    /// An iterator over the (dose, volume) pairs
+    pub fn iter(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over the (dose, volume) pairs in ascending dose order.
+    ///
+    /// The pairs are ordered as by [`DvhCheck::dvh_check`], but the DVH itself is not
+    /// modified, so this also works on an unsorted DVH through a shared reference.
+    ///
+    /// # Returns
+    /// An iterator over the (dose, volume) pairs sorted by dose
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let mut indices = (0..self.d.len()).collect::<Vec<_>>();
+        if !self.is_sorted {
+            indices.sort_unstable_by(|&i, &j| {
+                self.d[i]
+                    .total_cmp(&self.d[j])
+                    .then_with(|| self.v[j].total_cmp(&self.v[i]))
+            });
+        }
+        indices.into_iter().map(|i| (self.d[i], self.v[i]))
+    }
+
+    /// Removes the interior points of plateaus of equal volume.
+    ///
+    /// A data point is removed if its volume and the volume of the next data point both
+    /// equal the volume of the last kept data point within `vol_tol`. The first and last
+    /// data points of every plateau are kept, so with a `vol_tol` of 0 the DVH is
+    /// unchanged under linear interpolation. The DVH is sorted first if needed.
+    ///
+    /// # Parameters
+    /// - `vol_tol`: The tolerance within which volumes are considered equal
+    pub fn collapse_plateaus(&mut self, vol_tol: f64) {
+        self.sort();
+        let n = self.len();
+        if n < 3 {
+            return;
+        }
+        let mut d = Vec::with_capacity(n);
+        let mut v = Vec::with_capacity(n);
+        d.push(self.d[0]);
+        v.push(self.v[0]);
+        for i in 1..n - 1 {
+            let kept = v[v.len() - 1];
+            let interior =
+                (self.v[i] - kept).abs() <= vol_tol && (self.v[i + 1] - kept).abs() <= vol_tol;
+            if !interior {
+                d.push(self.d[i]);
+                v.push(self.v[i]);
+            }
+        }
+        d.push(self.d[n - 1]);
+        v.push(self.v[n - 1]);
+        self.d = d;
+        self.v = v;
+    }
+
+    /// Sorts the DVH data by dose, rejecting non-finite values instead of panicking.
+    ///
+    /// Data points are ordered as by [`DvhCheck::dvh_check`]. Data that bypassed the
+    /// validation of [`Dvh::add`], e.g. deserialized from an untrusted source, may contain
+    /// NaN or infinite values; the DVH is left unchanged in that case.
+    ///
+    /// # Errors
+    /// - `Error::NonFiniteValue`: If any dose or volume is NaN or infinite
+    pub fn sort_checked(&mut self) -> crate::Result<()> {
+        if self.d.iter().chain(self.v.iter()).any(|x| !x.is_finite()) {
+            return Err(Error::NonFiniteValue);
+        }
+        self.sort();
+        Ok(())
+    }
+
+    /// Resamples the DVH onto the dose points of another DVH.
+    ///
+    /// The volumes are obtained with [`Dvh::vx`], so both DVHs can afterwards be
+    /// compared point by point without supplying a dose grid.
+    ///
+    /// # Parameters
+    /// - `other`: The DVH whose dose points are used
+    ///
+    /// # Returns
+    /// A new sorted DVH with the dose points of `other`
+    ///
+    /// # Errors
+    /// - `Error::IncompatibleDvhUnits`: If the DVHs have different dose or volume units
+    /// - `Error::DvhUnsorted`: If `other` is not sorted
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn resample_to(&self, other: &Dvh) -> crate::Result<Dvh> {
+        if self.dose_unit != other.dose_unit || self.volume_unit != other.volume_unit {
+            return Err(Error::IncompatibleDvhUnits);
+        }
+        if !other.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+        self.resample_at(other.d.clone())
+    }
+
+    /// Calculates the integral dose of the structure.
+    ///
+    /// The integral dose is the mean dose (see [`Dvh::mean_dose`]) times the total
+    /// structure volume. The result is always expressed in Gy·cc, regardless of the
+    /// dose unit of the DVH.
+    ///
+    /// # Parameters
+    /// - `total_volume_cc`: The total volume of the structure in cc (must be positive)
+    ///
+    /// # Returns
+    /// The integral dose in Gy·cc
+    ///
+    /// # Errors
+    /// - `Error::InvalidTotalVolume`: If `total_volume_cc` is not positive
+    /// - Any error returned by [`Dvh::mean_dose`]
+    pub fn integral_dose(&self, total_volume_cc: f64) -> crate::Result<f64> {
+        if total_volume_cc.is_nan() || total_volume_cc <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        let mean_dose = self.to_dose_unit(DoseUnit::Gy).mean_dose()?;
+        Ok(mean_dose * total_volume_cc)
+    }
+
+    /// Writes the DVH as comma-separated values.
+    ///
+    /// The first row is a header with the units embedded in the column names, e.g.
+    /// `dose_Gy,volume_percent`, followed by one `dose,volume` row per data point in
+    /// ascending dose order. [Percent](VolumeUnit::Percent) volumes are written as stored,
+    /// as fractions in the range [0.0, 1.0].
+    ///
+    /// # Parameters
+    /// - `writer`: The destination of the CSV data
+    ///
+    /// # Errors
+    /// - `Error::Io`: If writing fails
+    #[cfg(feature = "csv")]
+    pub fn to_csv<W: std::io::Write>(&self, mut writer: W) -> crate::Result<()> {
+        let volume_unit = match self.volume_unit {
+            VolumeUnit::Percent => "percent",
+            VolumeUnit::Cc => "cc",
+        };
+        writeln!(writer, "dose_{},volume_{volume_unit}", self.dose_unit)?;
+        for (d, v) in self.iter_sorted() {
+            writeln!(writer, "{d},{v}")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a DVH from comma-separated values.
+    ///
+    /// Every non-empty line holds a dose and a volume, separated by a comma. The first
+    /// line is skipped as a header if it is not numeric, so the output of [`Dvh::to_csv`]
+    /// can be read back. [Percent](VolumeUnit::Percent) volumes are expected as fractions
+    /// in the range [0.0, 1.0]. The DVH is not sorted.
+    ///
+    /// # Parameters
+    /// - `reader`: The source of the CSV data
+    /// - `dose_type`: The unit type for dose measurements
+    /// - `volume_type`: The unit type for volume measurements
+    ///
+    /// # Returns
+    /// A new unsorted DVH containing the data points
+    ///
+    /// # Errors
+    /// - `Error::Io`: If reading fails
+    /// - `Error::ParseError`: If a line does not hold exactly two numeric values; the
+    ///   line number is 1-based
+    /// - Any error returned by [`Dvh::try_add`] for the first invalid data point
+    #[cfg(feature = "csv")]
+    pub fn from_csv<R: std::io::Read>(
+        reader: R,
+        dose_type: DoseUnit,
+        volume_type: VolumeUnit,
+    ) -> crate::Result<Dvh> {
+        use std::io::BufRead;
+
+        let mut dvh = Dvh::new(dose_type, volume_type);
+        for (i, line) in std::io::BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = line
+                .split(',')
+                .map(|field| field.trim().parse::<f64>())
+                .collect::<Vec<_>>();
+            match fields[..] {
+                [Ok(d), Ok(v)] => dvh.try_add(d, v)?,
+                // A non-numeric first line is a header.
+                [Err(_), ..] if i == 0 => continue,
+                _ => return Err(Error::ParseError { line: i + 1 }),
+            }
+        }
+        Ok(dvh)
+    }
+
+    /// Calculates the volume-weighted variance of the dose about the mean dose.
+    ///
+    /// The DVH is differentiated as in [`Dvh::mean_dose`] and the variance is computed
+    /// from the differential volumes normalized by the total volume, so the result has
+    /// the same meaning for both volume units. The DVH must be sorted before calling
+    /// this method.
+    ///
+    /// # Returns
+    /// The dose variance, in the square of the dose unit of the DVH
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If the DVH is empty
+    /// - `Error::DvhInsufficientData`: If the DVH has fewer than 2 data points
+    /// - `Error::DvhUnsorted`: If the DVH is not sorted
+    /// - `Error::InvalidTotalVolume`: If the total volume of the DVH is not positive
+    pub fn dose_variance(&self) -> crate::Result<f64> {
+        if self.is_empty() {
+            return Err(Error::DvhNoData);
+        }
+        if self.len() < 2 {
+            return Err(Error::DvhInsufficientData);
+        }
+        if !self.is_sorted {
+            return Err(Error::DvhUnsorted);
+        }
+
+        let (weighted_dose, total_volume) = self
+            .differential_bins()
+            .fold((0.0, 0.0), |(dose, volume), (d, v)| {
+                (dose + d * v, volume + v)
+            });
+        if total_volume <= 0.0 {
+            return Err(Error::InvalidTotalVolume);
+        }
+        let mean = weighted_dose / total_volume;
+        let weighted_deviation = self
+            .differential_bins()
+            .map(|(d, v)| v * (d - mean).powi(2))
+            .sum::<f64>();
+        Ok(weighted_deviation / total_volume)
+    }
+
+    /// Calculates the volume-weighted standard deviation of the dose.
+    ///
+    /// This is the square root of [`Dvh::dose_variance`].
+    ///
+    /// # Returns
+    /// The dose standard deviation, in the dose unit of the DVH
+    ///
+    /// # Errors
+    /// See [`Dvh::dose_variance`].
+    pub fn dose_std_dev(&self) -> crate::Result<f64> {
+        Ok(self.dose_variance()?.sqrt())
+    }
+
+    /// Calculates the minimum dose received by a fraction of the structure volume.
+    ///
+    /// Unlike [`Dvh::dx`], the volume is always given as a fraction of the structure
+    /// volume. For a [Cc](VolumeUnit::Cc) DVH the fraction is multiplied by the reference
+    /// volume, or by the largest volume if no reference volume is set, before querying.
+    ///
+    /// # Parameters
+    /// - `fraction`: The volume fraction [0.0, 1.0]
+    ///
+    /// # Returns
+    /// The dose value at the volume fraction
+    ///
+    /// # Errors
+    /// - `Error::FractionOutOfRange`: If `fraction` is outside [0.0, 1.0]
+    /// - `Error::InvalidTotalVolume`: If the total volume of a [Cc](VolumeUnit::Cc) DVH is
+    ///   not positive
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn dose_at_volume_fraction(&self, fraction: f64) -> crate::Result<f64> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(Error::FractionOutOfRange);
+        }
+        match self.volume_unit {
+            VolumeUnit::Percent => self.dx(fraction),
+            VolumeUnit::Cc => self.dx(fraction * self.resolve_total_volume(None)?),
+        }
+    }
+
+    /// Calculates the near-minimum dose D98%.
+    ///
+    /// ICRU report 83 recommends reporting D98%, the minimum dose received by 98% of the
+    /// structure volume, instead of the minimum dose, which depends on a single voxel
+    /// and is sensitive to the dose grid. The volume is a fraction of the structure volume
+    /// for both volume units, see [`Dvh::dose_at_volume_fraction`].
+    ///
+    /// # Returns
+    /// The near-minimum dose D98%
+    ///
+    /// # Errors
+    /// See [`Dvh::dose_at_volume_fraction`].
+    pub fn near_min_dose(&self) -> crate::Result<f64> {
+        self.dose_at_volume_fraction(0.98)
+    }
+
+    /// Calculates the near-maximum dose D2%.
+    ///
+    /// ICRU report 83 recommends reporting D2%, the minimum dose received by the hottest
+    /// 2% of the structure volume, instead of the maximum dose, which depends on a single
+    /// voxel and is sensitive to the dose grid. The volume is a fraction of the structure
+    /// volume for both volume units, see [`Dvh::dose_at_volume_fraction`].
+    ///
+    /// # Returns
+    /// The near-maximum dose D2%
+    ///
+    /// # Errors
+    /// See [`Dvh::dose_at_volume_fraction`].
+    pub fn near_max_dose(&self) -> crate::Result<f64> {
+        self.dose_at_volume_fraction(0.02)
+    }
+
+    /// Scales the doses in place so that the dose at a volume equals a target dose.
+    ///
+    /// This is the in-place counterpart of [`Dvh::renormalize_to`], e.g. to normalize a
+    /// plan so that D95% equals the prescription. The DVH is left unchanged on failure.
+    ///
+    /// # Parameters
+    /// - `target_dose`: The dose required at `at_volume` (must be positive)
+    /// - `at_volume`: The volume, in the [VolumeUnit] of the DVH
+    ///
+    /// # Errors
+    /// - `Error::InvalidPrescription`: If `target_dose` is not positive
+    /// - `Error::InvalidReferenceDose`: If the current dose at `at_volume` is 0
+    /// - Any error returned by [`Dvh::dx`]
+    pub fn normalize_to_prescription(
+        &mut self,
+        target_dose: f64,
+        at_volume: f64,
+    ) -> crate::Result<()> {
+        *self = self.renormalize_to(at_volume, target_dose)?;
+        Ok(())
+    }
+
+    /// Calculates the volume difference between two DVHs as a function of dose.
+    ///
+    /// Both DVHs are resampled with [`Dvh::vx`] onto the union of their dose points and
+    /// the volumes of `other` are subtracted from the volumes of `self`. The result is a
+    /// difference, not a standard DVH: its volumes can be negative and it fails
+    /// [`DvhCheck::dvh_check`]. It can still be queried with [`Dvh::vx`].
+    ///
+    /// # Parameters
+    /// - `other`: The DVH to subtract
+    ///
+    /// # Returns
+    /// A new sorted DVH with the volume differences `V_self(d) - V_other(d)`
+    ///
+    /// # Errors
+    /// - `Error::IncompatibleDvhUnits`: If the DVHs have different dose or volume units
+    /// - `Error::WrongDvhKind`: If either DVH is not a cumulative DVH
+    /// - Any error returned by [`Dvh::vx`]
+    pub fn volume_difference(&self, other: &Dvh) -> crate::Result<Dvh> {
+        if self.dose_unit != other.dose_unit || self.volume_unit != other.volume_unit {
+            return Err(Error::IncompatibleDvhUnits);
+        }
+        if self.kind != DvhKind::Cumulative || other.kind != DvhKind::Cumulative {
+            return Err(Error::WrongDvhKind);
+        }
+        self.combine_on_common_grid(other, |a, b| a - b)
+    }
+
+    /// Renders the DVH as an HTML table for quick human inspection.
+    ///
+    /// The table has a dose and a volume column, one row per data point in storage
+    /// order, and a caption listing the units. [Percent](VolumeUnit::Percent) volumes
+    /// are shown in the range [0.0, 100.0].
+    ///
+    /// # Returns
+    /// A `<table>` element as a string
+    #[cfg(feature = "html")]
+    pub fn to_html(&self) -> String {
+        let scale = match self.volume_unit {
+            VolumeUnit::Percent => 100.0,
+            VolumeUnit::Cc => 1.0,
+        };
+        let mut html = String::from("<table>\n");
+        html.push_str(&format!(
+            "<caption>DVH (dose: {}, volume: {})</caption>\n",
+            self.dose_unit, self.volume_unit
+        ));
+        html.push_str(&format!(
+            "<tr><th>Dose [{}]</th><th>Volume [{}]</th></tr>\n",
+            self.dose_unit, self.volume_unit
+        ));
+        for (d, v) in self.d.iter().zip(self.v.iter()) {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", d, v * scale));
+        }
+        html.push_str("</table>");
+        html
+    }
+}
+
+/// Sums per-beam differential DVHs of a structure into a cumulative composite DVH.
+///
+/// Cumulative DVHs cannot be summed meaningfully, so each beam DVH must be a
+/// [Differential](DvhKind::Differential) DVH in [Cc](VolumeUnit::Cc): the volume at each
+/// data point is the volume of the bin at that dose. The bin volumes of all beams are
+/// accumulated on `grid`, and the composite holds at each grid dose the total volume
+/// receiving at least that dose. Bin volumes below the first grid dose are not counted,
+/// so the grid should normally start at 0. The doses of all beams are converted to the
+/// dose unit of the first beam.
+///
+/// # Parameters
+/// - `beam_dvhs`: The differential DVHs of the beams
+/// - `grid`: The dose points of the composite, strictly ascending and non-negative
+///
+/// # Returns
+/// A sorted cumulative [Cc](VolumeUnit::Cc) DVH on `grid`
+///
+/// # Errors
+/// - `Error::DvhNoData`: If `beam_dvhs` is empty
+/// - `Error::InvalidDoseGrid`: If `grid` is empty, not strictly ascending or negative
+/// - `Error::WrongVolumeUnit`: If a beam DVH does not have [Cc](VolumeUnit::Cc) volumes
+/// - `Error::WrongDvhKind`: If a beam DVH is not a [Differential](DvhKind::Differential) DVH
+pub fn composite(beam_dvhs: &[Dvh], grid: &[f64]) -> crate::Result<Dvh> {
+    let Some(first) = beam_dvhs.first() else {
+        return Err(Error::DvhNoData);
+    };
+    if grid.is_empty() || grid[0] < 0.0 || grid.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(Error::InvalidDoseGrid);
+    }
+    if beam_dvhs
+        .iter()
+        .any(|dvh| dvh.volume_unit != VolumeUnit::Cc)
+    {
+        return Err(Error::WrongVolumeUnit);
+    }
+    if beam_dvhs
+        .iter()
+        .any(|dvh| dvh.kind != DvhKind::Differential)
+    {
+        return Err(Error::WrongDvhKind);
+    }
+
+    // Differential volume of the composite in each grid bin [grid[k], grid[k + 1]).
+    let mut bins = vec![0.0; grid.len()];
+    for dvh in beam_dvhs {
+        let dvh = dvh.to_dose_unit(first.dose_unit);
+        for (&d, &v) in dvh.d.iter().zip(dvh.v.iter()) {
+            let k = grid.partition_point(|&g| g <= d);
+            if k > 0 {
+                bins[k - 1] += v;
+            }
+        }
+    }
+    let mut v = bins;
+    for k in (0..v.len() - 1).rev() {
+        v[k] += v[k + 1];
+    }
+    Ok(Dvh {
+        d: grid.to_vec(),
+        v,
+        is_sorted: true,
+        ..Dvh::new(first.dose_unit, VolumeUnit::Cc)
+    })
+}
+
+/// Calculates the ratio of the volumes of two DVHs at each dose of a grid.
+///
+/// The volumes are obtained with [`Dvh::vx`]. Where the volume of `b` is 0 the ratio
+/// is undefined and reported as `NaN`, so the result always has one value per grid
+/// point. The DVHs must be [Cumulative](DvhKind::Cumulative). The doses of `b` are
+/// converted to the dose unit of `a`.
+///
+/// # Parameters
+/// - `a`: The DVH in the numerator
+/// - `b`: The DVH in the denominator
+/// - `grid`: The doses at which the ratio is calculated
+///
+/// # Returns
+/// The ratio `V_a(d) / V_b(d)` for each dose `d` of `grid`
+///
+/// # Errors
+/// - `Error::WrongVolumeUnit`: If the DVHs have different volume units
+/// - `Error::WrongDvhKind`: If either DVH is not a cumulative DVH
+/// - Any error returned by [`Dvh::vx`]
+pub fn volume_ratio(a: &Dvh, b: &Dvh, grid: &[f64]) -> crate::Result<Vec<f64>> {
+    if a.volume_unit != b.volume_unit {
+        return Err(Error::WrongVolumeUnit);
+    }
+    if a.kind != DvhKind::Cumulative || b.kind != DvhKind::Cumulative {
+        return Err(Error::WrongDvhKind);
+    }
+    let b = b.to_dose_unit(a.dose_unit);
+    grid.iter()
+        .map(|&dose| {
+            let denominator = b.vx(dose)?;
+            if denominator == 0.0 {
+                Ok(f64::NAN)
+            } else {
+                Ok(a.vx(dose)? / denominator)
+            }
+        })
+        .collect()
+}
+
+/// Calculates the weighted average of several DVHs on a dose grid.
+///
+/// Each DVH is sampled at the doses of `grid` with [`Dvh::vx`] and the volumes are
+/// combined with the given weights, which are normalized to sum to 1. This allows e.g.
+/// averaging DVHs of a patient cohort weighted by structure volume. The DVHs must be
+/// [Cumulative](DvhKind::Cumulative). The doses of all DVHs are converted to the dose
+/// unit of the first DVH.
+///
+/// # Parameters
+/// - `dvhs`: The DVHs to average
+/// - `weights`: The weight of each DVH (must be non-negative and not all zero)
+/// - `grid`: The dose points of the average, strictly ascending and non-negative
+///
+/// # Returns
+/// A sorted DVH on `grid` with the units of the first DVH
+///
+/// # Errors
+/// - `Error::DvhNoData`: If `dvhs` is empty
+/// - `Error::InvalidWeights`: If the number of weights differs from the number of DVHs,
+///   or if a weight is negative or all weights are zero
+/// - `Error::InvalidDoseGrid`: If `grid` is empty, not strictly ascending or negative
+/// - `Error::WrongVolumeUnit`: If the DVHs have different volume units
+/// - `Error::WrongDvhKind`: If a DVH is not a [Cumulative](DvhKind::Cumulative) DVH
+/// - Any error returned by [`Dvh::vx`]
+pub fn weighted_average(dvhs: &[Dvh], weights: &[f64], grid: &[f64]) -> crate::Result<Dvh> {
+    let Some(first) = dvhs.first() else {
+        return Err(Error::DvhNoData);
+    };
+    let total_weight = weights.iter().sum::<f64>();
+    if weights.len() != dvhs.len() || weights.iter().any(|&w| w < 0.0) || total_weight <= 0.0 {
+        return Err(Error::InvalidWeights);
+    }
+    if grid.is_empty() || grid[0] < 0.0 || grid.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(Error::InvalidDoseGrid);
+    }
+    if dvhs.iter().any(|dvh| dvh.volume_unit != first.volume_unit) {
+        return Err(Error::WrongVolumeUnit);
+    }
+    if dvhs.iter().any(|dvh| dvh.kind != DvhKind::Cumulative) {
+        return Err(Error::WrongDvhKind);
+    }
+
+    let mut v = vec![0.0; grid.len()];
+    for (dvh, &weight) in dvhs.iter().zip(weights) {
+        let dvh = dvh.to_dose_unit(first.dose_unit);
+        for (v, &dose) in v.iter_mut().zip(grid) {
+            *v += weight / total_weight * dvh.vx(dose)?;
+        }
+    }
+    Ok(Dvh {
+        d: grid.to_vec(),
+        v,
+        is_sorted: true,
+        ..Dvh::new(first.dose_unit, first.volume_unit)
+    })
+}
+
+/// Calculates the RTOG conformity index of a target.
+///
+/// The conformity index is the volume enclosed by the reference isodose, taken from
+/// the DVH of the body, divided by the volume of the target. The target volume is the
+/// reference volume of the target DVH if set and its maximum volume otherwise. A value
+/// of 1.0 means the reference isodose has the same volume as the target; larger values
+/// indicate that the reference isodose spills outside the target.
+///
+/// # Parameters
+/// - `target`: The DVH of the target
+/// - `body`: The DVH of the body or external structure
+/// - `reference_dose`: The reference isodose, e.g. the prescription dose
+///
+/// # Returns
+/// The conformity index `V_RI / V_target`
+///
+/// # Errors
+/// - `Error::IncompatibleDvhUnits`: If the DVHs are not both [Cc](VolumeUnit::Cc) DVHs
+///   with the same dose unit
+/// - `Error::WrongDvhKind`: If either DVH is not a cumulative DVH
+/// - `Error::InvalidTotalVolume`: If the target volume is not positive
+/// - Any error returned by [`Dvh::vx_abs`]
+pub fn conformity_index(target: &Dvh, body: &Dvh, reference_dose: f64) -> crate::Result<f64> {
+    if target.volume_unit != VolumeUnit::Cc
+        || body.volume_unit != VolumeUnit::Cc
+        || target.dose_unit != body.dose_unit
+    {
+        return Err(Error::IncompatibleDvhUnits);
+    }
+    if target.kind != DvhKind::Cumulative || body.kind != DvhKind::Cumulative {
+        return Err(Error::WrongDvhKind);
+    }
+    let target_volume = target.resolve_total_volume(None)?;
+    Ok(body.vx_abs(reference_dose)? / target_volume)
+}
+
+/// Calculates the fraction of the points of one DVH that agree with another DVH.
+///
+/// This is a one-dimensional analogue of the gamma index. For each data point of `a`,
+/// the distance to the piecewise linear curve of `b` is calculated with the dose and
+/// volume differences normalized by `dose_tol` and `volume_tol`. The point agrees if
+/// the distance is at most 1, i.e. if the curve of `b` passes within the tolerance
+/// ellipse around the point.
+///
+/// # Parameters
+/// - `a`: The DVH whose points are evaluated
+/// - `b`: The reference DVH
+/// - `dose_tol`: The dose tolerance, in the dose unit of the DVHs (must be positive)
+/// - `volume_tol`: The volume tolerance, in the volume unit of the DVHs (must be positive)
+///
+/// # Returns
+/// The fraction [0.0, 1.0] of the points of `a` that agree with `b`
+///
+/// # Errors
+/// - `Error::IncompatibleDvhUnits`: If the DVHs have different dose or volume units
+/// - `Error::WrongDvhKind`: If either DVH is not a cumulative DVH
+/// - `Error::InvalidTolerance`: If a tolerance is not positive
+/// - `Error::DvhNoData`: If either DVH is empty
+/// - `Error::DvhUnsorted`: If either DVH is not sorted
+pub fn dvh_agreement(a: &Dvh, b: &Dvh, dose_tol: f64, volume_tol: f64) -> crate::Result<f64> {
+    if a.dose_unit != b.dose_unit || a.volume_unit != b.volume_unit {
+        return Err(Error::IncompatibleDvhUnits);
+    }
+    if a.kind != DvhKind::Cumulative || b.kind != DvhKind::Cumulative {
+        return Err(Error::WrongDvhKind);
+    }
+    if !(dose_tol > 0.0 && volume_tol > 0.0) {
+        return Err(Error::InvalidTolerance);
+    }
+    if a.is_empty() || b.is_empty() {
+        return Err(Error::DvhNoData);
+    }
+    if !a.is_sorted || !b.is_sorted {
+        return Err(Error::DvhUnsorted);
+    }
+
+    // Squared normalized distance from (x, y) to the segment from p to q.
+    let distance = |(x, y): (f64, f64), p: (f64, f64), q: (f64, f64)| {
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        let length = dx * dx + dy * dy;
+        let t = if length > 0.0 {
+            (((x - p.0) * dx + (y - p.1) * dy) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (x - p.0 - t * dx).powi(2) + (y - p.1 - t * dy).powi(2)
+    };
+    let normalize = |(d, v): (f64, f64)| (d / dose_tol, v / volume_tol);
+    let curve = b.iter().map(normalize).collect::<Vec<_>>();
+    let agreeing = a
+        .iter()
+        .map(normalize)
+        .filter(|&point| {
+            let nearest = if curve.len() == 1 {
+                distance(point, curve[0], curve[0])
+            } else {
+                curve
+                    .windows(2)
+                    .map(|w| distance(point, w[0], w[1]))
+                    .fold(f64::INFINITY, f64::min)
+            };
+            nearest <= 1.0
+        })
+        .count();
+    Ok(agreeing as f64 / a.len() as f64)
+}
+
+impl Display for Dvh {
+    /// Formats a one-line summary of the DVH.
+    ///
+    /// The summary contains the units, the number of data points and the dose and
+    /// volume ranges, e.g. `DVH[Gy, %]: 6 pts, dose 0.0–50.0, vol 0.0–1.0`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DVH[{}, {}]: {} pts",
+            self.dose_unit,
+            self.volume_unit,
+            self.len()
+        )?;
+        if self.is_empty() {
+            return Ok(());
+        }
+        let range = |values: &[f64]| {
+            values
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| {
+                    (lo.min(x), hi.max(x))
+                })
+        };
+        let (d_min, d_max) = range(&self.d);
+        let (v_min, v_max) = range(&self.v);
+        write!(
+            f,
+            ", dose {:.1}–{:.1}, vol {:.1}–{:.1}",
+            d_min, d_max, v_min, v_max
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a Dvh {
+    type Item = (f64, f64);
+    type IntoIter = std::iter::Zip<
+        std::iter::Copied<std::slice::Iter<'a, f64>>,
+        std::iter::Copied<std::slice::Iter<'a, f64>>,
+    >;
+
+    /// Returns an iterator over the (dose, volume) pairs in storage order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.d.iter().copied().zip(self.v.iter().copied())
+    }
+}
+
+impl DvhCheck for Dvh {
+    /// Validates the DVH data.
+    ///
+    /// This method performs the following validation checks:
+    /// - Ensures that dose and volume vectors have the same length
+    /// - Verifies that all dose and volume values are finite
+    /// - Verifies that all dose values are non-negative
+    /// - Verifies that all volume values are non-negative
+    /// - If the volume type is [Percent](VolumeUnit::Percent), verifies that all volume values are in the range [0.0, 1.0]
+    /// - Sorts the DVH data by dose in ascending order if not already sorted
+    ///
+    /// # Returns
+    /// - `Ok(())` if all validations pass and data is successfully normalized
+    ///
+    /// # Errors
+    /// - `Error::MismatchedLengthDoseVolumeData`: If dose and volume vectors have different lengths
+    /// - `Error::NonFiniteValue`: If any dose or volume value is NaN or infinite
+    /// - `Error::NegativeDose`: If any dose value is negative
+    /// - `Error::NegativeVolume`: If any volume value is negative
+    /// - `Error::LikelyPercent100`: If the volume type is [Percent](VolumeUnit::Percent) and the volume values
+    ///   exceed 1.0 but lie within [0.0, 100.0]
+    /// - `Error::PercentVolumeOutOfRange`: If the volume type is [Percent](VolumeUnit::Percent) and any volume value exceeds 100.0
+    ///
+    /// # Example
+    /// ```
+    /// use dvh::{Dvh, DoseUnit, VolumeUnit, DvhCheck};
+    ///
+    /// let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+    /// dvh.add(10.0, 0.8);
+    /// dvh.add(5.0, 1.0);
+    /// dvh.add(15.0, 0.5);
+    ///
+    /// // Validate and sort the data
+    /// assert!(dvh.dvh_check().is_ok());
+    /// assert_eq!(dvh.doses(), vec![5.0, 10.0, 15.0]);
+    /// assert_eq!(dvh.volumes(), vec![1.0, 0.8, 0.5]);
+    /// ```
+    fn dvh_check(&mut self) -> crate::Result<()> {
+        if self.d.len() != self.v.len() {
+            return Err(Error::MismatchedLengthDoseVolumeData);
+        }
+        // Sorting panics on NaN values, so reject them before sorting.
+        if self.d.iter().chain(self.v.iter()).any(|x| !x.is_finite()) {
+            return Err(Error::NonFiniteValue);
+        }
+        for x in &self.d {
+            if *x < 0.0 {
+                return Err(Error::NegativeDose);
+            }
+        }
+        for x in &self.v {
+            if *x < 0.0 {
+                return Err(Error::NegativeVolume);
+            }
+        }
+        if self.volume_unit == VolumeUnit::Percent && self.v.iter().any(|&x| x > 1.0) {
+            // Volumes in [0.0, 100.0] are almost certainly percentages that were not
+            // divided by 100, rather than corrupt data.
+            if self.v.iter().all(|&x| x <= 100.0) {
+                return Err(Error::LikelyPercent100);
+            }
+            return Err(Error::PercentVolumeOutOfRange);
+        }
+        {
+            let is_sorted = self.is_sorted;
+            if !is_sorted {
+                self.sort();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MaxDose for Dvh {
+    /// Returns the maximum dose in the DVH.
+    ///
+    /// Non-finite doses (NaN or infinity) are ignored. If the DVH is empty, contains
+    /// only non-finite doses or only negative doses, 0.0 is returned.
+    fn max_dose(&self) -> f64 {
+        let mut doses = self.d.iter().copied().filter(|d| d.is_finite());
+        let max = if self.is_sorted {
+            doses.next_back()
+        } else {
+            doses.max_by(f64::total_cmp)
+        };
+        match max {
+            Some(a) if a >= 0.0 => a,
+            _ => 0.0,
+        }
+    }
+}
+
+impl MeanDose for Dvh {
+    /// Returns the volume-weighted mean dose, see [`Dvh::mean_dose`].
+    fn mean_dose(&self) -> crate::Result<f64> {
+        Dvh::mean_dose(self)
+    }
+}
+
+impl MinDose for Dvh {
+    /// Returns the smallest dose with a volume larger than 0.
+    ///
+    /// Data points with a zero volume, e.g. empty low-dose bins, are skipped, as are
+    /// non-finite doses. If no data point has a volume larger than 0, 0.0 is returned.
+    fn min_dose(&self) -> f64 {
+        self.d
+            .iter()
+            .zip(self.v.iter())
+            .filter(|&(d, &v)| d.is_finite() && v > 0.0)
+            .map(|(&d, _)| d)
+            .min_by(f64::total_cmp)
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(feature = "approx")]
+impl approx::AbsDiffEq for Dvh {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> Self::Epsilon {
+        f64::default_epsilon()
+    }
+
+    /// Compares two DVHs elementwise using an absolute difference.
+    ///
+    /// The dose and volume units must match and both DVHs must contain the same
+    /// number of data points.
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.dose_unit == other.dose_unit
+            && self.volume_unit == other.volume_unit
+            && self.d.len() == other.d.len()
+            && self.v.len() == other.v.len()
+            && self
+                .d
+                .iter()
+                .zip(other.d.iter())
+                .all(|(a, b)| a.abs_diff_eq(b, epsilon))
+            && self
+                .v
+                .iter()
+                .zip(other.v.iter())
+                .all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+}
+
+#[cfg(feature = "approx")]
+impl approx::RelativeEq for Dvh {
+    fn default_max_relative() -> Self::Epsilon {
+        f64::default_max_relative()
+    }
+
+    /// Compares two DVHs elementwise using a relative difference.
+    ///
+    /// The dose and volume units must match and both DVHs must contain the same
+    /// number of data points.
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.dose_unit == other.dose_unit
+            && self.volume_unit == other.volume_unit
+            && self.d.len() == other.d.len()
+            && self.v.len() == other.v.len()
+            && self
+                .d
+                .iter()
+                .zip(other.d.iter())
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+            && self
+                .v
+                .iter()
+                .zip(other.v.iter())
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+
+    #[test]
+    fn test_linear_interpolation_normal() {
+        let result = linear_interpolation(5.0, 0.0, 10.0, 0.0, 100.0);
+        assert_eq!(result, 50.0);
+    }
+
+    #[test]
+    fn test_linear_interpolation_same_x() {
+        let result = linear_interpolation(5.0, 10.0, 10.0, 20.0, 30.0);
+        assert_eq!(result, 20.0);
+    }
+
+    #[test]
+    fn test_linear_interpolation_boundary() {
+        let result = linear_interpolation(0.0, 0.0, 10.0, 0.0, 100.0);
+        assert_eq!(result, 0.0);
+
+        let result = linear_interpolation(10.0, 0.0, 10.0, 0.0, 100.0);
+        assert_eq!(result, 100.0);
+    }
+
+    #[test]
+    fn test_dvh_new() {
+        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(dvh.is_empty());
+        assert_eq!(dvh.len(), 0);
+        assert!(!dvh.is_sorted);
+    }
+
+    #[test]
+    fn test_dvh_new_cgy() {
+        let dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        assert!(dvh.is_empty());
+        assert!(matches!(dvh.dose_unit, DoseUnit::CGy));
+        assert!(matches!(dvh.volume_unit, VolumeUnit::Cc));
+    }
+
+    #[test]
+    fn test_dvh_len_and_is_empty() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert_eq!(dvh.len(), 0);
+        assert!(dvh.is_empty());
+
+        dvh.add(1.0, 1.0);
+        assert_eq!(dvh.len(), 1);
+        assert!(!dvh.is_empty());
+
+        dvh.add(2.0, 0.9);
+        assert_eq!(dvh.len(), 2);
+        assert!(!dvh.is_empty());
+    }
+
+    #[test]
+    fn test_dvh_add_valid() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(dvh.add(1.0, 1.0));
+        assert_eq!(dvh.len(), 1);
+        assert!(!dvh.is_sorted);
+    }
+
+    #[test]
+    fn test_dvh_add_negative_dose() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(!dvh.add(-1.0, 100.0));
+        assert_eq!(dvh.len(), 0);
+    }
+
+    #[test]
+    fn test_dvh_add_negative_volume() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(!dvh.add(1.0, -1.0));
+        assert_eq!(dvh.len(), 0);
+    }
+
+    #[test]
+    fn test_dvh_add_zero_values() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(dvh.add(0.0, 0.0));
+        assert_eq!(dvh.len(), 1);
+    }
+
+    #[test]
+    fn test_dvh_add_non_finite() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        assert!(!dvh.add(f64::NAN, 0.5));
+        assert!(!dvh.add(0.0, f64::INFINITY));
+        assert!(!dvh.add(f64::INFINITY, 0.5));
+        assert!(!dvh.add(0.0, f64::NAN));
+        assert!(dvh.is_empty());
+
+        assert!(!dvh.add_slice(&[0.0, f64::NAN], &[1.0, 0.5]));
+        assert!(!dvh.add_slice(&[0.0, 1.0], &[f64::NEG_INFINITY, 0.5]));
+        assert!(dvh.is_empty());
+
+        // Only finite values reach the vectors, so sorting cannot panic.
+        dvh.add(f64::NAN, f64::NAN);
+        dvh.add_slice(&[2.0, 1.0], &[0.5, 1.0]);
+        dvh.sort();
+        assert_eq!(dvh.doses(), &[1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_dvh_from_points() {
+        let dvh = Dvh::from_points(
+            DoseUnit::Gy,
+            VolumeUnit::Percent,
+            &[(10.0, 0.5), (0.0, 1.0), (20.0, 0.0)],
+        )
+        .unwrap();
+        assert_eq!(dvh.doses(), &[10.0, 0.0, 20.0]);
+        assert_eq!(dvh.volumes(), &[0.5, 1.0, 0.0]);
+        assert!(!dvh.is_sorted);
+    }
+
+    #[test]
+    fn test_dvh_from_points_invalid() {
+        let from_points =
+            |points: &[(f64, f64)]| Dvh::from_points(DoseUnit::Gy, VolumeUnit::Percent, points);
+        assert!(matches!(
+            from_points(&[(0.0, 1.0), (-1.0, 0.5)]),
+            Err(Error::NegativeDose)
+        ));
+        assert!(matches!(
+            from_points(&[(0.0, -0.5)]),
+            Err(Error::NegativeVolume)
+        ));
+        assert!(matches!(
+            from_points(&[(0.0, 1.5)]),
+            Err(Error::PercentVolumeOutOfRange)
+        ));
+        assert!(matches!(
+            from_points(&[(f64::NAN, 0.5)]),
+            Err(Error::NonFiniteValue)
+        ));
+        // Volumes above 1.0 are fine for a cc DVH.
+        assert!(Dvh::from_points(DoseUnit::Gy, VolumeUnit::Cc, &[(0.0, 1.5)]).is_ok());
+    }
+
+    #[test]
+    fn test_dvh_try_add_errors() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(matches!(dvh.try_add(-1.0, 0.5), Err(Error::NegativeDose)));
+        assert!(matches!(dvh.try_add(1.0, -0.5), Err(Error::NegativeVolume)));
+        assert!(matches!(
+            dvh.try_add(1.0, 1.5),
+            Err(Error::PercentVolumeOutOfRange)
+        ));
+        assert!(matches!(
+            dvh.try_add(f64::NAN, 0.5),
+            Err(Error::NonFiniteValue)
+        ));
+        assert!(dvh.is_empty());
+        dvh.try_add(1.0, 0.5).unwrap();
+        assert_eq!(dvh.len(), 1);
+    }
+
+    #[test]
+    fn test_dvh_try_add_slice_errors() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(matches!(
+            dvh.try_add_slice(&[0.0, 1.0], &[1.0]),
+            Err(Error::MismatchedLengthDoseVolumeData)
+        ));
+        assert!(matches!(
+            dvh.try_add_slice(&[0.0, -1.0], &[1.0, 0.5]),
+            Err(Error::NegativeDose)
+        ));
+        assert!(matches!(
+            dvh.try_add_slice(&[0.0, 1.0], &[1.0, -0.5]),
+            Err(Error::NegativeVolume)
+        ));
+        assert!(matches!(
+            dvh.try_add_slice(&[0.0, 1.0], &[1.5, 0.5]),
+            Err(Error::PercentVolumeOutOfRange)
+        ));
+        assert!(dvh.is_empty());
+        dvh.try_add_slice(&[0.0, 1.0], &[1.0, 0.5]).unwrap();
+        assert_eq!(dvh.len(), 2);
+    }
+
+    #[test]
+    fn test_dvh_add_slice_valid() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let doses = vec![1.0, 2.0, 3.0];
+        let volumes = vec![1.0, 0.9, 0.8];
+        assert!(dvh.add_slice(&doses, &volumes));
+        assert_eq!(dvh.len(), 3);
+        assert!(!dvh.is_sorted);
+    }
+
+    #[test]
+    fn test_dvh_add_slice_mismatched_length() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let doses = vec![1.0, 2.0];
+        let volumes = vec![100.0, 90.0, 80.0];
+        assert!(!dvh.add_slice(&doses, &volumes));
+        assert_eq!(dvh.len(), 0);
+    }
+
+    #[test]
+    fn test_dvh_add_slice_negative_dose() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let doses = vec![1.0, -2.0, 3.0];
+        let volumes = vec![100.0, 90.0, 80.0];
+        assert!(!dvh.add_slice(&doses, &volumes));
+        assert_eq!(dvh.len(), 0);
+    }
+
+    #[test]
+    fn test_dvh_add_slice_negative_volume() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let doses = vec![1.0, 2.0, 3.0];
+        let volumes = vec![1.0, -0.9, 0.8];
+        assert!(!dvh.add_slice(&doses, &volumes));
+        assert_eq!(dvh.len(), 0);
+    }
+
+    #[test]
+    fn test_dvh_add_slice_empty() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let doses: Vec<f64> = vec![];
+        let volumes: Vec<f64> = vec![];
+        assert!(dvh.add_slice(&doses, &volumes));
+        assert_eq!(dvh.len(), 0);
+    }
+
+    #[test]
+    fn test_dvh_sort() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(3.0, 0.8);
+        dvh.add(1.0, 1.0);
+        dvh.add(2.0, 0.9);
+
+        dvh.sort();
+
+        assert!(dvh.is_sorted);
+        assert_eq!(dvh.d, vec![1.0, 2.0, 3.0]);
+        assert_eq!(dvh.v, vec![1.0, 0.9, 0.8]);
+    }
+
+    #[test]
+    fn test_dvh_sort_duplicate_doses() {
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        a.add_slice(&[2.0, 1.0, 2.0, 0.0, 2.0], &[0.5, 0.9, 0.7, 1.0, 0.6]);
+        let mut b = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        b.add_slice(&[2.0, 2.0, 0.0, 2.0, 1.0], &[0.6, 0.7, 1.0, 0.5, 0.9]);
+
+        a.sort();
+        b.sort();
+
+        assert_eq!(a.d, vec![0.0, 1.0, 2.0, 2.0, 2.0]);
+        assert_eq!(a.v, vec![1.0, 0.9, 0.7, 0.6, 0.5]);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_dvh_sorted() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[20.0, 0.0, 10.0], &[0.0, 1.0, 0.5]);
+
+        let sorted = dvh.sorted();
+        assert_eq!(sorted.doses(), &[0.0, 10.0, 20.0]);
+        assert_ulps_eq!(sorted.dx(0.5).unwrap(), 10.0);
+
+        assert!(!dvh.is_sorted);
+        assert_eq!(dvh.doses(), &[20.0, 0.0, 10.0]);
+        assert!(matches!(dvh.dx(0.5), Err(Error::DvhUnsorted)));
+    }
+
+    #[test]
+    fn test_dvh_iter() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[20.0, 0.0, 10.0, 10.0], &[0.0, 1.0, 0.4, 0.5]);
+
+        let pairs = dvh.iter().collect::<Vec<_>>();
+        let expected = dvh
+            .doses()
+            .iter()
+            .copied()
+            .zip(dvh.volumes().iter().copied())
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, expected);
+        assert_eq!((&dvh).into_iter().collect::<Vec<_>>(), expected);
+
+        let sorted = dvh.iter_sorted().collect::<Vec<_>>();
+        assert_eq!(
+            sorted,
+            vec![(0.0, 1.0), (10.0, 0.5), (10.0, 0.4), (20.0, 0.0)]
+        );
+        assert!(!dvh.is_sorted);
+        assert_eq!(dvh.doses(), &[20.0, 0.0, 10.0, 10.0]);
+    }
+
+    #[test]
+    fn test_dvh_sort_checked() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[10.0, 0.0], &[0.5, 1.0]);
+        dvh.sort_checked().unwrap();
+        assert_eq!(dvh.doses(), &[0.0, 10.0]);
+
+        // `add` rejects NaN values, so set the data directly.
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![10.0, f64::NAN, 0.0];
+        dvh.v = vec![0.5, 0.8, 1.0];
+        assert!(matches!(dvh.sort_checked(), Err(Error::NonFiniteValue)));
+        assert!(!dvh.is_sorted);
+        assert_eq!(dvh.volumes(), &[0.5, 0.8, 1.0]);
+    }
+
+    #[test]
+    fn test_dvh_sort_already_sorted() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.add(2.0, 0.9);
+        dvh.sort();
+
+        // Sort again should not change anything
+        dvh.sort();
+
+        assert!(dvh.is_sorted);
+        assert_eq!(dvh.d, vec![1.0, 2.0]);
+        assert_eq!(dvh.v, vec![1.0, 0.9]);
+    }
+
+    #[test]
+    fn test_dvh_dx_negative_volume() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.add(2.0, 0.9);
+        dvh.sort();
+
+        let result = dvh.dx(-10.0);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NegativeVolume));
+    }
+
+    #[test]
+    fn test_dvh_dx_empty() {
+        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let result = dvh.dx(50.0);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::DvhNoData));
+    }
+
+    #[test]
+    fn test_dvh_dx_insufficient_data() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.sort();
+
+        let result = dvh.dx(50.0);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::DvhInsufficientData));
+    }
+
+    #[test]
+    fn test_dvh_dx_unsorted() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.add(2.0, 0.9);
+        // Don't sort
+
+        let result = dvh.dx(0.95);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::DvhUnsorted));
+    }
+
+    #[test]
+    fn test_dvh_dx_interpolation() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.dx(0.9);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_dvh_dx_below_minimum() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.dx(0.7);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 10.0);
+    }
+
+    #[test]
+    fn test_dvh_dx_above_maximum() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.dx(1.1);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_dvh_dx_exact_match() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(5.0, 0.9);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.dx(0.9);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_dvh_dx_top_dose_segment() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 50.0, 60.0, 70.0], &[1.0, 0.9, 0.5, 0.1]);
+        dvh.sort();
+
+        // 0.3 lies between the two highest dose points (60 Gy, 0.5) and (70 Gy, 0.1).
+        assert_ulps_eq!(dvh.dx(0.3).unwrap(), 65.0);
+        assert_ulps_eq!(dvh.dx(0.2).unwrap(), 67.5);
+    }
+
+    #[test]
+    fn test_dvh_dx_multiple_points() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(5.0, 0.9);
+        dvh.add(10.0, 0.8);
+        dvh.add(15.0, 0.7);
+        dvh.sort();
+
+        // Test interpolation between different segments
+        let result = dvh.dx(0.85);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 7.5);
+
+        let result = dvh.dx(0.79);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 10.5);
+
+        let result = dvh.dx(0.71);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 14.5);
+    }
+
+    #[test]
+    fn test_dvh_vx_negative_dose() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.add(2.0, 0.9);
+        dvh.sort();
+
+        let result = dvh.vx(-1.0);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NegativeDose));
+    }
+
+    #[test]
+    fn test_dvh_vx_empty() {
+        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let result = dvh.vx(5.0);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::DvhNoData));
+    }
+
+    #[test]
+    fn test_dvh_vx_insufficient_data() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.sort();
+
+        let result = dvh.vx(1.0);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::DvhInsufficientData));
+    }
+
+    #[test]
+    fn test_dvh_vx_unsorted() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(1.0, 1.0);
+        dvh.add(2.0, 0.9);
+        // Don't sort
+
+        let result = dvh.vx(1.5);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::DvhUnsorted));
+    }
+
+    #[test]
+    fn test_dvh_vx_below_minimum() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(5.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.vx(3.0);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_dvh_vx_above_maximum() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(5.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.vx(15.0);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 0.8);
+    }
+
+    #[test]
+    fn test_dvh_vx_exact_match() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(5.0, 0.9);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.vx(5.0);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 0.9);
+    }
+
+    #[test]
+    fn test_dvh_vx_interpolation() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let result = dvh.vx(5.0);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 0.9);
+
+        let result = dvh.vx(2.0);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 0.96);
+
+        let result = dvh.vx(8.0);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 0.84);
+    }
+
+    #[test]
+    fn test_dvh_vx_multiple_points() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(5.0, 0.9);
+        dvh.add(10.0, 0.8);
+        dvh.add(15.0, 0.7);
+        dvh.sort();
+
+        // Test interpolation between different segments
+        let result = dvh.vx(7.5);
+        assert!(result.is_ok());
+        assert_ulps_eq!(result.unwrap(), 0.85);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_dvh_serde() {
+        let mut dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        dvh.add(0.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.sort();
+
+        let serialized = serde_json::to_string(&dvh).unwrap();
+        let mut deserialized: Dvh = serde_json::from_str(&serialized).unwrap();
+        deserialized.sort();
+
+        assert_eq!(deserialized.dose_unit, DoseUnit::CGy);
+        assert_eq!(deserialized.volume_unit, VolumeUnit::Cc);
+        assert_eq!(deserialized.len(), 2);
+        assert_ulps_eq!(deserialized.dx(0.9).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_dvh_check_mismatched_lengths() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![1.0, 2.0, 3.0];
+        dvh.v = vec![1.0, 0.9];
+
+        let result = dvh.dvh_check();
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            Error::MismatchedLengthDoseVolumeData
+        ));
+    }
+
+    #[test]
+    fn test_dvh_check_negative_dose() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![1.0, -2.0, 3.0];
+        dvh.v = vec![1.0, 0.9, 0.8];
+
+        let result = dvh.dvh_check();
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NegativeDose));
+    }
+
+    #[test]
+    fn test_dvh_check_negative_volume() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![1.0, 2.0, 3.0];
+        dvh.v = vec![1.0, -0.9, 0.8];
+
+        let result = dvh.dvh_check();
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::NegativeVolume));
+    }
+
+    #[test]
+    fn test_dvh_check_percent_volume_out_of_range() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![1.0, 2.0, 3.0];
+        dvh.v = vec![1.0, 150.0, 0.8];
+
+        let result = dvh.dvh_check();
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            Error::PercentVolumeOutOfRange
+        ));
+    }
+
+    #[test]
+    fn test_dvh_check_likely_percent_100() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![0.0, 10.0, 20.0];
+        dvh.v = vec![100.0, 50.0, 0.0];
+
+        let result = dvh.dvh_check();
+        assert!(matches!(result, Err(Error::LikelyPercent100)));
+        // The data is left untouched so the caller can rescale it.
+        assert_eq!(dvh.volumes(), &[100.0, 50.0, 0.0]);
+    }
+
+    #[test]
+    fn test_dvh_check_success_with_sorting() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(10.0, 0.8);
+        dvh.add(5.0, 1.0);
+        dvh.add(15.0, 0.5);
+
+        let result = dvh.dvh_check();
+        assert!(result.is_ok());
+        assert!(dvh.is_sorted);
+        assert_eq!(dvh.doses(), vec![5.0, 10.0, 15.0]);
+        assert_eq!(dvh.volumes(), vec![1.0, 0.8, 0.5]);
+    }
+
+    #[test]
+    fn test_dvh_check_non_finite() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![10.0, f64::NAN, 5.0];
+        dvh.v = vec![0.8, 0.9, 1.0];
+        assert!(matches!(dvh.dvh_check(), Err(Error::NonFiniteValue)));
+        assert!(!dvh.is_sorted);
+
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.d = vec![0.0, 10.0];
+        dvh.v = vec![f64::INFINITY, 0.0];
+        assert!(matches!(dvh.dvh_check(), Err(Error::NonFiniteValue)));
+    }
+
+    #[test]
+    fn test_dvh_check_sorted_enables_queries() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[20.0, 0.0, 10.0], &[0.0, 1.0, 0.5]);
+        assert!(matches!(dvh.vx(5.0), Err(Error::DvhUnsorted)));
+
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.vx(5.0).unwrap(), 0.75);
+        assert_ulps_eq!(dvh.dx(0.5).unwrap(), 10.0);
+    }
+
+    #[test]
+    fn test_dvh_check_empty() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+
+        let result = dvh.dvh_check();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_dvh_check_already_sorted() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(5.0, 1.0);
+        dvh.add(10.0, 0.8);
+        dvh.add(15.0, 0.5);
+        dvh.sort();
+
+        let result = dvh.dvh_check();
+        assert!(result.is_ok());
+        assert!(dvh.is_sorted);
+        assert_eq!(dvh.doses(), vec![5.0, 10.0, 15.0]);
+        assert_eq!(dvh.volumes(), vec![1.0, 0.8, 0.5]);
+    }
+
+    #[test]
+    fn test_max_dose_empty() {
+        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert_eq!(dvh.max_dose(), 0.0);
+    }
+
+    #[test]
+    fn test_max_dose_single_value() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(42.5, 1.0);
+        assert_ulps_eq!(dvh.max_dose(), 42.5);
+    }
+
+    #[test]
+    fn test_max_dose_multiple_values() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(10.0, 1.0);
+        dvh.add(25.0, 0.8);
+        dvh.add(15.0, 0.9);
+        dvh.add(50.0, 0.5);
+        dvh.add(30.0, 0.7);
+        assert_ulps_eq!(dvh.max_dose(), 50.0);
+    }
+
+    #[test]
+    fn test_max_dose_with_negative_values() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![-5.0, -10.0, -2.0];
+        dvh.v = vec![1.0, 0.8, 0.9];
+        assert_eq!(dvh.max_dose(), 0.0);
+    }
+
+    #[test]
+    fn test_max_dose_all_zeros() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(0.0, 1.0);
+        dvh.add(0.0, 0.8);
+        dvh.add(0.0, 0.5);
+        assert_eq!(dvh.max_dose(), 0.0);
+    }
+
+    #[test]
+    fn test_max_dose_unsorted() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add(30.0, 0.7);
+        dvh.add(10.0, 1.0);
+        dvh.add(50.0, 0.5);
+        dvh.add(25.0, 0.8);
+        assert_ulps_eq!(dvh.max_dose(), 50.0);
+    }
+
+    #[test]
+    #[cfg(feature = "approx")]
+    fn test_dvh_approx_relative_eq() {
+        use approx::{assert_abs_diff_eq, assert_relative_eq, assert_relative_ne};
+
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        a.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.5, 0.0]);
+        let mut b = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        b.add_slice(&[0.0, 10.0 + 1e-12, 20.0], &[1.0, 0.5 - 1e-12, 0.0]);
+
+        assert_relative_eq!(a, b, epsilon = 1e-9);
+        assert_abs_diff_eq!(a, b, epsilon = 1e-9);
+
+        let mut c = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        c.add_slice(&[0.0, 11.0, 20.0], &[1.0, 0.5, 0.0]);
+        assert_relative_ne!(a, c, epsilon = 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "approx")]
+    fn test_dvh_approx_requires_matching_units() {
+        use approx::{assert_relative_ne, relative_eq};
+
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        a.add_slice(&[0.0, 10.0], &[5.0, 0.0]);
+        let mut b = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        b.add_slice(&[0.0, 10.0], &[5.0, 0.0]);
+        assert_relative_ne!(a, b);
+
+        let mut c = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        c.add_slice(&[0.0], &[5.0]);
+        assert!(!relative_eq!(a, c));
+    }
+
+    #[test]
+    fn test_dose_to_outer_cc_cc_dvh() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 30.0], &[10.0, 5.0, 1.0, 0.0]);
+        dvh.sort();
+
+        // D0.1cc lies in the top segment between (20 Gy, 1 cc) and (30 Gy, 0 cc).
+        assert_ulps_eq!(dvh.dose_to_outer_cc(0.1, None).unwrap(), 29.0);
+        assert_ulps_eq!(dvh.dose_to_outer_cc(5.0, None).unwrap(), 10.0);
+        // The structure volume is not needed for absolute volumes and is ignored.
+        assert_ulps_eq!(dvh.dose_to_outer_cc(0.1, Some(500.0)).unwrap(), 29.0);
+    }
+
+    #[test]
+    fn test_dose_to_outer_cc_percent_dvh() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 30.0], &[1.0, 0.5, 0.1, 0.0]);
+        dvh.sort();
+
+        assert_ulps_eq!(dvh.dose_to_outer_cc(0.1, Some(10.0)).unwrap(), 29.0);
+
+        dvh.reference_volume_cc = Some(10.0);
+        assert_ulps_eq!(dvh.dose_to_outer_cc(0.1, None).unwrap(), 29.0);
+    }
+
+    #[test]
+    fn test_dose_to_outer_cc_invalid_input() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        dvh.sort();
+
+        assert!(matches!(
+            dvh.dose_to_outer_cc(-0.1, Some(10.0)),
+            Err(Error::NegativeVolume)
+        ));
+        assert!(matches!(
+            dvh.dose_to_outer_cc(0.1, Some(0.0)),
+            Err(Error::InvalidTotalVolume)
+        ));
+        assert!(matches!(
+            dvh.dose_to_outer_cc(0.1, None),
+            Err(Error::VolumeUnitConversionRequired)
+        ));
+    }
+
+    #[test]
+    fn test_reinterpret_volume_unit_refuses() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0], &[1.0, 0.5]);
+
+        let result = dvh.reinterpret_volume_unit(VolumeUnit::Cc);
+        assert!(matches!(result, Err(Error::WrongVolumeUnit)));
+
+        let same = dvh.reinterpret_volume_unit(VolumeUnit::Percent).unwrap();
+        assert_eq!(same, dvh);
+    }
+
+    #[test]
+    fn test_to_absolute_and_percent_volume() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.5, 0.0]);
+
+        let cc = dvh.to_absolute_volume(Some(40.0)).unwrap();
+        assert_eq!(cc.volume_unit, VolumeUnit::Cc);
+        assert_eq!(cc.volumes(), &[40.0, 20.0, 0.0]);
+        assert_eq!(cc.reference_volume_cc, Some(40.0));
+
+        let percent = cc.to_percent_volume(None).unwrap();
+        assert_eq!(percent.volumes(), dvh.volumes());
+        assert_eq!(percent.volume_unit, VolumeUnit::Percent);
+
+        assert!(matches!(
+            dvh.to_percent_volume(Some(40.0)),
+            Err(Error::WrongVolumeUnit)
+        ));
+        assert!(matches!(
+            dvh.to_absolute_volume(Some(0.0)),
+            Err(Error::InvalidTotalVolume)
+        ));
+        assert!(matches!(
+            dvh.to_absolute_volume(None),
+            Err(Error::VolumeUnitConversionRequired)
+        ));
+        assert!(matches!(
+            cc.to_percent_volume(Some(20.0)),
+            Err(Error::PercentVolumeOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn test_dx_sorted_queries_matches_dx() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(
+            &[0.0, 5.0, 10.0, 15.0, 20.0, 25.0],
+            &[1.0, 1.0, 0.9, 0.6, 0.2, 0.0],
+        );
+        dvh.sort();
+
+        let queries = [1.1, 1.0, 0.95, 0.9, 0.75, 0.6, 0.4, 0.2, 0.05, 0.0];
+        let batched = dvh.dx_sorted_queries(&queries).unwrap();
+        assert_eq!(batched.len(), queries.len());
+        for (volume, dose) in queries.iter().zip(batched.iter()) {
+            assert_ulps_eq!(*dose, dvh.dx(*volume).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dx_sorted_queries_rejects_ascending() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        dvh.sort();
+
+        let result = dvh.dx_sorted_queries(&[0.5, 0.9]);
+        assert!(matches!(result, Err(Error::UnsortedQueries)));
+    }
+
+    #[test]
+    #[cfg(feature = "html")]
+    fn test_dvh_to_html() {
+        let mut dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 1000.0, 2000.0], &[1.0, 0.5, 0.0]);
+
+        let html = dvh.to_html();
+        assert!(html.starts_with("<table>"));
+        assert!(html.ends_with("</table>"));
+        // One header row and one row per data point.
+        assert_eq!(html.matches("<tr>").count(), 4);
+        assert!(html.contains("<caption>DVH (dose: cGy, volume: %)</caption>"));
+        assert!(html.contains("<tr><td>1000</td><td>50</td></tr>"));
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_dvh_to_csv() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[20.0, 0.0, 10.5], &[0.0, 1.0, 0.25]);
+
+        let mut buffer = Vec::new();
+        dvh.to_csv(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "dose_Gy,volume_percent\n0,1\n10.5,0.25\n20,0\n"
+        );
+
+        let mut buffer = Vec::new();
+        Dvh::new(DoseUnit::CGy, VolumeUnit::Cc)
+            .to_csv(&mut buffer)
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "dose_cGy,volume_cc\n");
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_dvh_from_csv() {
+        let csv = "0,1.0\n10,0.5\n\n20,0.0\n";
+        let dvh = Dvh::from_csv(csv.as_bytes(), DoseUnit::Gy, VolumeUnit::Percent).unwrap();
+        assert_eq!(dvh.doses(), &[0.0, 10.0, 20.0]);
+        assert_eq!(dvh.volumes(), &[1.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_dvh_from_csv_with_header() {
+        let mut dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 1000.0, 2000.0], &[40.0, 12.5, 0.0]);
+        let mut buffer = Vec::new();
+        dvh.to_csv(&mut buffer).unwrap();
+
+        let read = Dvh::from_csv(buffer.as_slice(), DoseUnit::CGy, VolumeUnit::Cc).unwrap();
+        assert_eq!(read.doses(), dvh.doses());
+        assert_eq!(read.volumes(), dvh.volumes());
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_dvh_from_csv_invalid() {
+        let from_csv = |csv: &str| Dvh::from_csv(csv.as_bytes(), DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(matches!(
+            from_csv("dose,volume\n0,1.0\n10,abc\n"),
+            Err(Error::ParseError { line: 3 })
+        ));
+        assert!(matches!(
+            from_csv("0,1.0\n10\n"),
+            Err(Error::ParseError { line: 2 })
+        ));
+        assert!(matches!(
+            from_csv("0,1.0\n-10,0.5\n"),
+            Err(Error::NegativeDose)
+        ));
+        assert!(matches!(
+            from_csv("0,1.0\n10,1.5\n"),
+            Err(Error::PercentVolumeOutOfRange)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn test_dvh_to_svg() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 25.0, 50.0], &[1.0, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        let svg = dvh.to_svg(100, 20).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert!(svg.contains("points=\"0.00,0.00 50.00,10.00 100.00,20.00\""));
+
+        assert!(matches!(
+            Dvh::default().to_svg(100, 20),
+            Err(Error::DvhNoData)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_to_volume_unit() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.4, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        let cc = dvh.to_volume_unit(VolumeUnit::Cc, 50.0).unwrap();
+        assert_eq!(cc.volume_unit, VolumeUnit::Cc);
+        assert_eq!(cc.reference_volume_cc, Some(50.0));
+        assert_ulps_eq!(cc.volumes(), [50.0, 20.0, 0.0].as_slice());
+
+        let percent = cc.to_volume_unit(VolumeUnit::Percent, 50.0).unwrap();
+        assert_eq!(percent.volume_unit, VolumeUnit::Percent);
+        assert_ulps_eq!(percent.volumes(), dvh.volumes());
+
+        let same = dvh.to_volume_unit(VolumeUnit::Percent, 50.0).unwrap();
+        assert_ulps_eq!(same.volumes(), dvh.volumes());
+    }
+
+    #[test]
+    fn test_dvh_to_volume_unit_invalid() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0], &[40.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert!(matches!(
+            dvh.to_volume_unit(VolumeUnit::Percent, 0.0),
+            Err(Error::InvalidTotalVolume)
+        ));
+        assert!(matches!(
+            dvh.to_volume_unit(VolumeUnit::Percent, 30.0),
+            Err(Error::PercentVolumeOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_banded_volume_fractions() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 30.0], &[40.0, 30.0, 10.0, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        let fractions = dvh
+            .banded_volume_fractions(&[5.0, 10.0, 20.0, 25.0])
+            .unwrap();
+        assert_eq!(fractions.len(), 3);
+        assert_ulps_eq!(fractions[0], 0.125);
+        assert_ulps_eq!(fractions[1], 0.5);
+        assert_ulps_eq!(fractions[2], 0.125);
+        // The bands cover the volume between V5Gy (35 cc) and V25Gy (5 cc).
+        assert_ulps_eq!(fractions.iter().sum::<f64>(), 0.75);
+
+        assert!(matches!(
+            dvh.banded_volume_fractions(&[10.0, 5.0]),
+            Err(Error::UnsortedQueries)
+        ));
+        assert!(matches!(
+            dvh.banded_volume_fractions(&[10.0]),
+            Err(Error::InvalidDoseGrid)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_resample() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 25.0], &[1.0, 0.8, 0.2, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        let resampled = dvh.resample(2.5).unwrap();
+        assert_eq!(resampled.len(), 11);
+        assert_ulps_eq!(resampled.doses()[1], 2.5);
+        assert_ulps_eq!(resampled.doses()[10], 25.0);
+        for dose in [1.0, 7.3, 12.5, 18.9, 24.0] {
+            assert_ulps_eq!(
+                resampled.vx(dose).unwrap(),
+                dvh.vx(dose).unwrap(),
+                epsilon = 1e-12
+            );
+        }
+
+        assert!(matches!(dvh.resample(0.0), Err(Error::InvalidBinWidth)));
+        assert!(matches!(dvh.resample(-1.0), Err(Error::InvalidBinWidth)));
+    }
+
+    #[test]
+    fn test_dvh_merge() {
+        let mut left = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        left.add_slice(&[0.0, 10.0, 20.0], &[1000.0, 400.0, 0.0]);
+        left.dvh_check().unwrap();
+
+        let merged = left.merge(&left).unwrap();
+        assert_eq!(merged.doses(), left.doses());
+        assert_ulps_eq!(merged.volumes(), [2000.0, 800.0, 0.0].as_slice());
+
+        let mut right = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        right.add_slice(&[0.0, 5.0, 30.0], &[800.0, 600.0, 0.0]);
+        right.dvh_check().unwrap();
+        let merged = left.merge(&right).unwrap();
+        assert_eq!(merged.doses(), [0.0, 5.0, 10.0, 20.0, 30.0].as_slice());
+        assert_ulps_eq!(merged.max_dose(), 30.0);
+        assert_ulps_eq!(merged.vx(0.0).unwrap(), 1800.0);
+        assert_ulps_eq!(merged.vx(10.0).unwrap(), 400.0 + 480.0);
+    }
+
+    #[test]
+    fn test_dvh_merge_incompatible_units() {
+        let mut gy = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        gy.add_slice(&[0.0, 10.0], &[100.0, 0.0]);
+        gy.dvh_check().unwrap();
+        let cgy = gy.to_dose_unit(DoseUnit::CGy);
+        assert!(matches!(gy.merge(&cgy), Err(Error::IncompatibleDvhUnits)));
+
+        let mut percent = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        percent.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        percent.dvh_check().unwrap();
+        assert!(matches!(
+            gy.merge(&percent),
+            Err(Error::IncompatibleDvhUnits)
+        ));
+        assert!(matches!(
+            percent.merge(&percent),
+            Err(Error::WrongVolumeUnit)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_has_coverage() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[0.0, 0.0, 0.0]);
+        assert!(!dvh.has_coverage());
+
+        let mut single = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        single.add(0.0, 10.0);
+        assert!(!single.has_coverage());
+
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[40.0, 20.0, 0.0]);
+        assert!(dvh.has_coverage());
+    }
+
+    #[test]
+    fn test_dvh_format_dose_and_volume() {
+        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert_eq!(dvh.format_dose(60.0), "60.0 Gy");
+        assert_eq!(dvh.format_dose(59.96), "60.0 Gy");
+        assert_eq!(dvh.format_volume(0.95), "95.0 %");
+
+        let dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        assert_eq!(dvh.format_dose(6000.0), "6000 cGy");
+        assert_eq!(dvh.format_volume(195.26), "195.3 cc");
+    }
+
+    #[test]
+    fn test_dvh_check_prescription_sanity() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 60.0, 62.0], &[1.0, 1.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert!(
+            dvh.check_prescription_sanity(60.0, 120.0, 95.0)
+                .unwrap()
+                .is_empty()
+        );
+
+        let mut overdosed = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        overdosed.add_slice(&[0.0, 60.0, 78.0], &[1.0, 1.0, 0.0]);
+        overdosed.dvh_check().unwrap();
+        let warnings = overdosed
+            .check_prescription_sanity(60.0, 120.0, 95.0)
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Maximum dose 78.0 Gy exceeds 120% of the prescription (130.0%)."]
+        );
+
+        let warnings = dvh.check_prescription_sanity(70.0, 120.0, 95.0).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("D95 60.1 Gy is below 95%"));
+
+        assert!(matches!(
+            dvh.check_prescription_sanity(0.0, 120.0, 95.0),
+            Err(Error::InvalidPrescription)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_collapse_plateaus() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        let doses = (0..=50).map(f64::from).collect::<Vec<_>>();
+        let volumes = doses
+            .iter()
+            .map(|&d| if d <= 40.0 { 1.0 } else { (50.0 - d) / 10.0 })
+            .collect::<Vec<_>>();
+        dvh.add_slice(&doses, &volumes);
+        dvh.dvh_check().unwrap();
+        let original = dvh.clone();
+
+        dvh.collapse_plateaus(0.0);
+        // The plateau [0, 40] Gy is reduced to its endpoints, the fall-off is kept.
+        assert_eq!(dvh.len(), 2 + 10);
+        assert_eq!(&dvh.doses()[..3], &[0.0, 40.0, 41.0]);
+        for dose in [0.0, 12.3, 39.9, 40.0, 44.5, 50.0] {
+            assert_ulps_eq!(dvh.vx(dose).unwrap(), original.vx(dose).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dvh_resample_to() {
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        a.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.6, 0.0]);
+        a.dvh_check().unwrap();
+        let mut b = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        b.add_slice(&[0.0, 5.0, 15.0, 25.0], &[1.0, 0.9, 0.5, 0.0]);
+        b.dvh_check().unwrap();
+
+        let aligned = b.resample_to(&a).unwrap();
+        assert_eq!(aligned.doses(), a.doses());
+        assert_ulps_eq!(aligned.volumes(), [1.0, 0.7, 0.25].as_slice());
+
+        let cgy = a.to_dose_unit(DoseUnit::CGy);
+        assert!(matches!(
+            b.resample_to(&cgy),
+            Err(Error::IncompatibleDvhUnits)
+        ));
+    }
+
+    #[test]
+    fn test_conformity_index() {
+        let cc = |doses: &[f64], volumes: &[f64]| {
+            let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+            dvh.add_slice(doses, volumes);
+            dvh.dvh_check().unwrap();
+            dvh
+        };
+        let target = cc(&[0.0, 60.0, 62.0], &[100.0, 100.0, 0.0]);
+
+        // The 60 Gy isodose encloses exactly the target.
+        let body = cc(&[0.0, 60.0, 62.0], &[5000.0, 100.0, 0.0]);
+        assert_ulps_eq!(conformity_index(&target, &body, 60.0).unwrap(), 1.0);
+
+        // The 60 Gy isodose spills 50 cc outside the target.
+        let body = cc(&[0.0, 60.0, 62.0], &[5000.0, 150.0, 0.0]);
+        let ci = conformity_index(&target, &body, 60.0).unwrap();
+        assert!(ci > 1.0);
+        assert_ulps_eq!(ci, 1.5);
+        let mut with_reference = target.clone();
+        with_reference.reference_volume_cc = Some(150.0);
+        assert_ulps_eq!(conformity_index(&with_reference, &body, 60.0).unwrap(), 1.0);
+
+        let mut percent = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        percent.add_slice(&[0.0, 60.0], &[1.0, 0.0]);
+        percent.dvh_check().unwrap();
+        assert!(matches!(
+            conformity_index(&percent, &body, 60.0),
+            Err(Error::IncompatibleDvhUnits)
+        ));
+        let differential = body.to_differential().unwrap();
+        assert!(matches!(
+            conformity_index(&target, &differential, 60.0),
+            Err(Error::WrongDvhKind)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_integral_dose() {
+        // The whole structure receives 50 Gy.
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 50.0], &[1.0, 1.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.integral_dose(200.0).unwrap(), 50.0 * 200.0);
+
+        let cgy = dvh.to_dose_unit(DoseUnit::CGy);
+        assert_ulps_eq!(cgy.integral_dose(200.0).unwrap(), 50.0 * 200.0);
+
+        let cc = dvh.to_volume_unit(VolumeUnit::Cc, 200.0).unwrap();
+        assert_ulps_eq!(cc.integral_dose(200.0).unwrap(), 50.0 * 200.0);
+
+        assert!(matches!(
+            dvh.integral_dose(0.0),
+            Err(Error::InvalidTotalVolume)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_to_eqd2() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 30.0, 60.0], &[1.0, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        let unchanged = dvh.to_eqd2(3.0, 2.0).unwrap();
+        assert_ulps_eq!(unchanged.doses(), dvh.doses());
+        assert_eq!(unchanged.volumes(), dvh.volumes());
+
+        // 5 x 6 Gy with α/β = 3 Gy: EQD2 = 30 * (6 + 3) / (2 + 3) = 54 Gy.
+        let hypo = dvh.to_eqd2(3.0, 6.0).unwrap();
+        assert_ulps_eq!(hypo.doses()[1], 54.0);
+
+        let cgy = dvh.to_dose_unit(DoseUnit::CGy);
+        assert_ulps_eq!(cgy.to_eqd2(300.0, 200.0).unwrap().doses(), cgy.doses());
+
+        assert!(matches!(
+            dvh.to_eqd2(0.0, 2.0),
+            Err(Error::InvalidRadiobiologyParameter)
+        ));
+        assert!(matches!(
+            dvh.to_eqd2(3.0, -2.0),
+            Err(Error::InvalidRadiobiologyParameter)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_dose_variance() {
+        // The whole structure receives 50 Gy.
+        let mut uniform = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        uniform.add_slice(&[0.0, 50.0], &[1.0, 1.0]);
+        uniform.dvh_check().unwrap();
+        assert_ulps_eq!(uniform.dose_std_dev().unwrap(), 0.0, epsilon = 1e-12);
+
+        // Half of the structure receives 20 Gy, the other half 40 Gy.
+        let mut two_level = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        two_level.add_slice(&[0.0, 20.0, 20.0, 40.0], &[80.0, 80.0, 40.0, 40.0]);
+        two_level.dvh_check().unwrap();
+        assert_ulps_eq!(two_level.dose_variance().unwrap(), 100.0);
+        assert_ulps_eq!(two_level.dose_std_dev().unwrap(), 10.0);
+
+        let mut unsorted = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        unsorted.add_slice(&[10.0, 0.0], &[0.0, 1.0]);
+        assert!(matches!(unsorted.dose_variance(), Err(Error::DvhUnsorted)));
+    }
+
+    #[test]
+    fn test_dvh_dose_at_volume_fraction() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 30.0], &[80.0, 60.0, 20.0]);
+        dvh.dvh_check().unwrap();
+
+        assert_ulps_eq!(
+            dvh.dose_at_volume_fraction(0.5).unwrap(),
+            dvh.dx(40.0).unwrap()
+        );
+        assert_ulps_eq!(dvh.dose_at_volume_fraction(0.5).unwrap(), 20.0);
+        assert!(matches!(
+            dvh.dose_at_volume_fraction(1.5),
+            Err(Error::FractionOutOfRange)
+        ));
+        assert!(matches!(
+            dvh.dose_at_volume_fraction(-0.1),
+            Err(Error::FractionOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn test_dvh_near_min_and_max_dose() {
+        // Steep target DVH falling from 100% at 57 Gy to 0% at 63 Gy.
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 57.0, 63.0], &[1.0, 1.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.near_min_dose().unwrap(), 57.12, epsilon = 1e-12);
+        assert_ulps_eq!(dvh.near_max_dose().unwrap(), 62.88, epsilon = 1e-12);
+        assert!(dvh.near_min_dose().unwrap() > dvh.min_dose());
+        assert!(dvh.near_max_dose().unwrap() < dvh.max_dose());
+
+        let cc = dvh.to_volume_unit(VolumeUnit::Cc, 250.0).unwrap();
+        assert_ulps_eq!(cc.near_min_dose().unwrap(), 57.12, epsilon = 1e-12);
+        assert_ulps_eq!(cc.near_max_dose().unwrap(), 62.88, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_dvh_normalize_to_prescription() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 50.0, 60.0], &[1.0, 1.0, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        dvh.normalize_to_prescription(60.0, 0.95).unwrap();
+        assert_ulps_eq!(dvh.dx(0.95).unwrap(), 60.0, epsilon = 1e-12);
+
+        let mut cold = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        cold.add_slice(&[0.0, 10.0], &[0.5, 0.0]);
+        cold.dvh_check().unwrap();
+        let original = cold.clone();
+        assert!(matches!(
+            cold.normalize_to_prescription(60.0, 0.95),
+            Err(Error::InvalidReferenceDose)
+        ));
+        assert_eq!(cold, original);
     }
 
     #[test]
-    fn test_linear_interpolation_boundary() {
-        let result = linear_interpolation(0.0, 0.0, 10.0, 0.0, 100.0);
-        assert_eq!(result, 0.0);
+    fn test_dvh_clamp_percent_volumes() {
+        // `add` rejects out-of-range percent volumes, so set the data directly.
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![0.0, 10.0, 20.0];
+        dvh.v = vec![1.0000001, 0.5, -0.0000001];
+        dvh.clamp_percent_volumes();
+        assert_eq!(dvh.volumes(), &[1.0, 0.5, 0.0]);
 
-        let result = linear_interpolation(10.0, 0.0, 10.0, 0.0, 100.0);
-        assert_eq!(result, 100.0);
+        dvh.v = vec![1.5, 1.0000001, 0.0];
+        dvh.clamp_percent_volumes();
+        // The clearly invalid volume is left for validation to reject.
+        assert_eq!(dvh.volumes(), &[1.5, 1.0, 0.0]);
+        assert!(matches!(dvh.dvh_check(), Err(Error::LikelyPercent100)));
     }
 
     #[test]
-    fn test_dvh_new() {
-        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert!(dvh.is_empty());
-        assert_eq!(dvh.len(), 0);
-        assert!(!dvh.is_sorted);
+    fn test_dvh_total_volume() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 30.0], &[125.0, 125.0, 60.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.total_volume().unwrap(), dvh.vx(0.0).unwrap());
+        assert_ulps_eq!(dvh.total_volume().unwrap(), 125.0);
+        let mut with_reference = dvh.clone();
+        with_reference.reference_volume_cc = Some(130.0);
+        assert_ulps_eq!(with_reference.total_volume().unwrap(), 130.0);
+
+        let percent = dvh.to_volume_unit(VolumeUnit::Percent, 125.0).unwrap();
+        assert!(matches!(
+            percent.total_volume(),
+            Err(Error::VolumeUnitConversionRequired)
+        ));
+        assert!(matches!(
+            Dvh::new(DoseUnit::Gy, VolumeUnit::Cc).total_volume(),
+            Err(Error::DvhNoData)
+        ));
     }
 
     #[test]
-    fn test_dvh_new_cgy() {
-        let dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
-        assert!(dvh.is_empty());
-        assert!(matches!(dvh.dose_unit, DoseUnit::CGy));
-        assert!(matches!(dvh.volume_unit, VolumeUnit::Cc));
+    fn test_dvh_volume_difference() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.6, 0.0]);
+        dvh.dvh_check().unwrap();
+
+        let zero = dvh.volume_difference(&dvh).unwrap();
+        assert_eq!(zero.doses(), dvh.doses());
+        assert!(zero.volumes().iter().all(|&v| v == 0.0));
+
+        let mut other = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        other.add_slice(&[0.0, 5.0, 20.0], &[1.0, 1.0, 0.0]);
+        other.dvh_check().unwrap();
+        let diff = dvh.volume_difference(&other).unwrap();
+        assert_eq!(diff.doses(), &[0.0, 5.0, 10.0, 20.0]);
+        assert_ulps_eq!(
+            diff.volumes(),
+            [0.0, -0.2, -0.0666666666666667, 0.0].as_slice(),
+            epsilon = 1e-12
+        );
+
+        let cc = dvh.to_volume_unit(VolumeUnit::Cc, 10.0).unwrap();
+        assert!(matches!(
+            dvh.volume_difference(&cc),
+            Err(Error::IncompatibleDvhUnits)
+        ));
+        let differential = dvh.to_differential().unwrap();
+        assert!(matches!(
+            dvh.volume_difference(&differential),
+            Err(Error::WrongDvhKind)
+        ));
     }
 
     #[test]
-    fn test_dvh_len_and_is_empty() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert_eq!(dvh.len(), 0);
-        assert!(dvh.is_empty());
+    fn test_dvh_agreement() {
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        a.add_slice(&[0.0, 10.0, 20.0, 30.0, 40.0], &[1.0, 1.0, 0.5, 0.1, 0.0]);
+        a.dvh_check().unwrap();
+        assert_ulps_eq!(dvh_agreement(&a, &a, 1.0, 0.01).unwrap(), 1.0);
 
-        dvh.add(1.0, 1.0);
-        assert_eq!(dvh.len(), 1);
-        assert!(!dvh.is_empty());
+        // Shifting the dose axis by 5 Gy moves the fall-off out of the 1 Gy tolerance,
+        // only the two points on the 100% plateau still agree.
+        let mut shifted = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        shifted.add_slice(&[0.0, 15.0, 25.0, 35.0, 45.0], &[1.0, 1.0, 0.5, 0.1, 0.0]);
+        shifted.dvh_check().unwrap();
+        let score = dvh_agreement(&a, &shifted, 1.0, 0.01).unwrap();
+        assert!(score < 1.0);
+        assert_ulps_eq!(score, 0.4);
+        // A tolerance larger than the shift accepts every point.
+        assert_ulps_eq!(dvh_agreement(&a, &shifted, 5.0, 0.01).unwrap(), 1.0);
 
-        dvh.add(2.0, 0.9);
-        assert_eq!(dvh.len(), 2);
-        assert!(!dvh.is_empty());
+        assert!(matches!(
+            dvh_agreement(&a, &shifted, 0.0, 0.01),
+            Err(Error::InvalidTolerance)
+        ));
+        let differential = a.to_differential().unwrap();
+        assert!(matches!(
+            dvh_agreement(&differential, &a, 1.0, 0.01),
+            Err(Error::WrongDvhKind)
+        ));
     }
 
     #[test]
-    fn test_dvh_add_valid() {
+    fn test_resample_log_geometric_spacing() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert!(dvh.add(1.0, 1.0));
-        assert_eq!(dvh.len(), 1);
-        assert!(!dvh.is_sorted);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 40.0], &[1.0, 0.9, 0.5, 0.0]);
+        dvh.sort();
+
+        let resampled = dvh.resample_log(5, 1.0, 16.0).unwrap();
+        assert_eq!(resampled.len(), 5);
+        assert!(resampled.is_sorted);
+        let doses = resampled.doses();
+        assert_ulps_eq!(doses[0], 1.0);
+        assert_ulps_eq!(doses[4], 16.0);
+        for w in doses.windows(2) {
+            assert_ulps_eq!(w[1] / w[0], 2.0, max_ulps = 8);
+        }
+        assert_ulps_eq!(resampled.volumes()[0], dvh.vx(1.0).unwrap());
+        assert_ulps_eq!(resampled.volumes()[4], dvh.vx(16.0).unwrap());
     }
 
     #[test]
-    fn test_dvh_add_negative_dose() {
+    fn test_resample_log_invalid_parameters() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert!(!dvh.add(-1.0, 100.0));
-        assert_eq!(dvh.len(), 0);
+        dvh.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        dvh.sort();
+
+        assert!(matches!(
+            dvh.resample_log(1, 1.0, 10.0),
+            Err(Error::InvalidDoseGrid)
+        ));
+        assert!(matches!(
+            dvh.resample_log(5, 0.0, 10.0),
+            Err(Error::InvalidDoseGrid)
+        ));
+        assert!(matches!(
+            dvh.resample_log(5, 10.0, 10.0),
+            Err(Error::InvalidDoseGrid)
+        ));
     }
 
     #[test]
-    fn test_dvh_add_negative_volume() {
+    fn test_dvh_display() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert!(!dvh.add(1.0, -1.0));
-        assert_eq!(dvh.len(), 0);
+        dvh.add_slice(
+            &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0],
+            &[1.0, 1.0, 0.98, 0.95, 0.5, 0.0],
+        );
+        assert_eq!(
+            dvh.to_string(),
+            "DVH[Gy, %]: 6 pts, dose 0.0–50.0, vol 0.0–1.0"
+        );
+
+        let empty = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        assert_eq!(empty.to_string(), "DVH[cGy, cc]: 0 pts");
     }
 
     #[test]
-    fn test_dvh_add_zero_values() {
+    fn test_normalized_overlay() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert!(dvh.add(0.0, 0.0));
-        assert_eq!(dvh.len(), 1);
+        dvh.add_slice(&[0.0, 30.0, 60.0, 66.0], &[1.0, 1.0, 0.95, 0.0]);
+
+        let (doses, volumes) = dvh.normalized_overlay(60.0).unwrap();
+        assert_ulps_eq!(doses[2], 100.0);
+        assert_ulps_eq!(doses[1], 50.0);
+        assert_ulps_eq!(doses[3], 110.0);
+        assert_eq!(volumes, dvh.volumes());
+
+        assert!(matches!(
+            dvh.normalized_overlay(0.0),
+            Err(Error::InvalidPrescription)
+        ));
     }
 
     #[test]
-    fn test_dvh_add_slice_valid() {
+    fn test_mean_dose_between() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let doses = vec![1.0, 2.0, 3.0];
-        let volumes = vec![1.0, 0.9, 0.8];
-        assert!(dvh.add_slice(&doses, &volumes));
-        assert_eq!(dvh.len(), 3);
-        assert!(!dvh.is_sorted);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.5, 0.0]);
+        dvh.sort();
+
+        // Half of the volume is spread uniformly over [0, 10] and half over [10, 20].
+        assert_ulps_eq!(dvh.mean_dose_between(0.0, 10.0).unwrap(), 5.0);
+        assert_ulps_eq!(dvh.mean_dose_between(10.0, 20.0).unwrap(), 15.0);
+        assert_ulps_eq!(dvh.mean_dose_between(5.0, 15.0).unwrap(), 10.0);
+        assert_ulps_eq!(dvh.mean_dose_between(0.0, 20.0).unwrap(), 10.0);
     }
 
     #[test]
-    fn test_dvh_add_slice_mismatched_length() {
+    fn test_mean_dose_between_invalid_window() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let doses = vec![1.0, 2.0];
-        let volumes = vec![100.0, 90.0, 80.0];
-        assert!(!dvh.add_slice(&doses, &volumes));
-        assert_eq!(dvh.len(), 0);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.5, 0.0]);
+
+        assert!(matches!(
+            dvh.mean_dose_between(0.0, 10.0),
+            Err(Error::DvhUnsorted)
+        ));
+        dvh.sort();
+        assert!(matches!(
+            dvh.mean_dose_between(-1.0, 10.0),
+            Err(Error::NegativeDose)
+        ));
+        assert!(matches!(
+            dvh.mean_dose_between(10.0, 10.0),
+            Err(Error::InvalidDoseWindow)
+        ));
+        assert!(matches!(
+            dvh.mean_dose_between(30.0, 40.0),
+            Err(Error::InvalidDoseWindow)
+        ));
     }
 
     #[test]
-    fn test_dvh_add_slice_negative_dose() {
+    #[cfg(feature = "serde")]
+    fn test_dvh_serde_reference_volume() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let doses = vec![1.0, -2.0, 3.0];
-        let volumes = vec![100.0, 90.0, 80.0];
-        assert!(!dvh.add_slice(&doses, &volumes));
-        assert_eq!(dvh.len(), 0);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.5, 0.0]);
+        dvh.reference_volume_cc = Some(40.0);
+
+        let serialized = serde_json::to_string(&dvh).unwrap();
+        assert!(serialized.contains("\"reference_volume_cc\":40.0"));
+        let deserialized: Dvh = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.reference_volume_cc, Some(40.0));
+
+        let cc = deserialized.to_absolute_volume(None).unwrap();
+        assert_eq!(cc.volume_unit, VolumeUnit::Cc);
+        assert_eq!(cc.volumes(), &[40.0, 20.0, 0.0]);
     }
 
     #[test]
-    fn test_dvh_add_slice_negative_volume() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let doses = vec![1.0, 2.0, 3.0];
-        let volumes = vec![1.0, -0.9, 0.8];
-        assert!(!dvh.add_slice(&doses, &volumes));
-        assert_eq!(dvh.len(), 0);
+    fn test_dvh_slice() {
+        let mut dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        dvh.add_slice(
+            &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0],
+            &[5.0, 4.0, 3.0, 2.0, 1.0, 0.0],
+        );
+        dvh.sort();
+
+        let middle = dvh.slice(2..5).unwrap();
+        assert_eq!(middle.doses(), &[20.0, 30.0, 40.0]);
+        assert_eq!(middle.volumes(), &[3.0, 2.0, 1.0]);
+        assert_eq!(middle.dose_unit, DoseUnit::CGy);
+        assert_eq!(middle.volume_unit, VolumeUnit::Cc);
+        assert!(middle.is_sorted);
+
+        assert!(matches!(dvh.slice(4..7), Err(Error::IndexOutOfRange)));
     }
 
     #[test]
-    fn test_dvh_add_slice_empty() {
+    fn test_points_above_and_below_dose() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let doses: Vec<f64> = vec![];
-        let volumes: Vec<f64> = vec![];
-        assert!(dvh.add_slice(&doses, &volumes));
-        assert_eq!(dvh.len(), 0);
+        dvh.add_slice(
+            &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0],
+            &[1.0, 0.9, 0.7, 0.4, 0.1, 0.0],
+        );
+        dvh.dvh_check().unwrap();
+
+        assert_eq!(dvh.points_above_dose(30.0), 2);
+        assert_eq!(dvh.points_below_dose(30.0), 3);
+        assert_eq!(dvh.points_above_dose(50.0), 0);
+        assert_eq!(dvh.points_below_dose(0.0), 0);
+        assert_eq!(dvh.points_above_dose(-1.0), 6);
     }
 
     #[test]
-    fn test_dvh_sort() {
+    fn test_to_eqd2_per_voxel() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(3.0, 0.8);
-        dvh.add(1.0, 1.0);
-        dvh.add(2.0, 0.9);
+        dvh.add_slice(&[0.0, 30.0, 60.0], &[1.0, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
 
-        dvh.sort();
+        let eqd2 = dvh.to_eqd2_per_voxel(3.0, 30).unwrap();
+        // 30 Gy in 30 fractions: 1 Gy/fx -> 30 * (1 + 3) / (2 + 3) = 24 Gy
+        // 60 Gy in 30 fractions: 2 Gy/fx -> 60 * (2 + 3) / (2 + 3) = 60 Gy
+        let expected: Vec<f64> = [0.0, 30.0, 60.0]
+            .iter()
+            .map(|&d: &f64| d * (d / 30.0 + 3.0) / (2.0 + 3.0))
+            .collect();
+        for (a, b) in eqd2.doses().iter().zip(expected.iter()) {
+            assert_ulps_eq!(*a, *b);
+        }
+        assert_ulps_eq!(eqd2.doses()[1], 24.0);
+        assert_ulps_eq!(eqd2.doses()[2], 60.0);
+        assert_eq!(eqd2.volumes(), dvh.volumes());
+        assert!(eqd2.prescription_dose.is_none());
 
-        assert!(dvh.is_sorted);
-        assert_eq!(dvh.d, vec![1.0, 2.0, 3.0]);
-        assert_eq!(dvh.v, vec![1.0, 0.9, 0.8]);
+        // 30 Gy in 5 fractions: 6 Gy/fx -> 30 * (6 + 3) / (2 + 3) = 54 Gy
+        let mut prescribed = dvh.clone();
+        prescribed.prescription_dose = Some(30.0);
+        let eqd2 = prescribed.to_eqd2_per_voxel(3.0, 5).unwrap();
+        assert_ulps_eq!(eqd2.prescription_dose.unwrap(), 54.0);
+        assert_ulps_eq!(eqd2.doses()[1], 54.0);
+
+        for alpha_beta in [0.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                dvh.to_eqd2_per_voxel(alpha_beta, 30),
+                Err(Error::InvalidRadiobiologyParameter)
+            ));
+        }
+        assert!(matches!(
+            dvh.to_eqd2_per_voxel(3.0, 0),
+            Err(Error::InvalidFractionation)
+        ));
     }
 
     #[test]
-    fn test_dvh_sort_already_sorted() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.add(2.0, 0.9);
-        dvh.sort();
+    fn test_structure_volume_starting_at_zero() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[40.0, 20.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.structure_volume().unwrap(), 40.0);
+    }
 
-        // Sort again should not change anything
-        dvh.sort();
+    #[test]
+    fn test_structure_volume_missing_zero_dose_point() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[5.0, 10.0, 20.0], &[40.0, 20.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert!(matches!(
+            dvh.structure_volume(),
+            Err(Error::MissingZeroDosePoint)
+        ));
 
-        assert!(dvh.is_sorted);
-        assert_eq!(dvh.d, vec![1.0, 2.0]);
-        assert_eq!(dvh.v, vec![1.0, 0.9]);
+        let mut plateau = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        plateau.add_slice(&[5.0, 10.0, 20.0], &[40.0, 40.0, 0.0]);
+        plateau.dvh_check().unwrap();
+        assert_ulps_eq!(plateau.structure_volume().unwrap(), 40.0);
     }
 
     #[test]
-    fn test_dvh_dx_negative_volume() {
+    fn test_dx_respects_stored_interp_mode() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.add(2.0, 0.9);
-        dvh.sort();
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.1, 0.001]);
+        dvh.dvh_check().unwrap();
+        assert_eq!(dvh.interp_mode(), InterpolationMode::Linear);
+        assert_ulps_eq!(dvh.dx(0.01).unwrap(), 10.0 + 10.0 * 0.09 / 0.099);
 
-        let result = dvh.dx(-10.0);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::NegativeVolume));
+        dvh.set_interp_mode(InterpolationMode::LogLinear);
+        // ln(0.01) lies halfway between ln(0.1) and ln(0.001)
+        assert_ulps_eq!(dvh.dx(0.01).unwrap(), 15.0);
+        assert_ulps_eq!(dvh.vx(15.0).unwrap(), 0.01, max_ulps = 8);
+        assert_ulps_eq!(
+            dvh.dx(0.01).unwrap(),
+            dvh.dx_with(0.01, InterpolationMode::LogLinear).unwrap()
+        );
+        assert_ulps_eq!(dvh.dx_sorted_queries(&[0.01]).unwrap()[0], 15.0);
     }
 
     #[test]
-    fn test_dvh_dx_empty() {
-        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let result = dvh.dx(50.0);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::DvhNoData));
+    fn test_validate_cc_range() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[100000.0, 2000.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        let result = dvh.validate_cc_range(5000.0);
+        assert!(matches!(
+            result,
+            Err(Error::ImplausibleVolume { index: 0, value }) if value == 100000.0
+        ));
+        assert!(dvh.validate_cc_range(200000.0).is_ok());
+
+        let percent = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(matches!(
+            percent.validate_cc_range(5000.0),
+            Err(Error::WrongVolumeUnit)
+        ));
     }
 
     #[test]
-    fn test_dvh_dx_insufficient_data() {
+    fn test_max_dose_ignores_non_finite_values() {
+        // `add_slice` rejects non-finite values, so set the data directly.
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.sort();
+        dvh.d = vec![10.0, f64::NAN, 30.0, 20.0];
+        dvh.v = vec![0.8, 0.5, 0.1, 0.3];
+        assert_ulps_eq!(dvh.max_dose(), 30.0);
 
-        let result = dvh.dx(50.0);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::DvhInsufficientData));
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.d = vec![f64::NAN, f64::INFINITY];
+        dvh.v = vec![1.0, 0.0];
+        assert_ulps_eq!(dvh.max_dose(), 0.0);
     }
 
     #[test]
-    fn test_dvh_dx_unsorted() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.add(2.0, 0.9);
-        // Don't sort
+    fn test_matches_reference_on_grid() {
+        let mut reference = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        reference.add_slice(&[0.0, 20.0, 40.0], &[1.0, 0.6, 0.2]);
+        reference.dvh_check().unwrap();
 
-        let result = dvh.dx(0.95);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::DvhUnsorted));
+        // Same piecewise linear curve, sampled on more points.
+        let mut computed = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        computed.add_slice(
+            &[0.0, 5.0, 10.0, 20.0, 30.0, 40.0],
+            &[1.0, 0.9, 0.8, 0.6, 0.4, 0.2],
+        );
+        computed.dvh_check().unwrap();
+
+        let grid = [0.0, 7.5, 15.0, 25.0, 35.0, 40.0];
+        assert!(computed.matches_reference(&reference, &grid, 1e-9));
+
+        let mut different = computed.clone();
+        different.v[3] = 0.5;
+        assert!(!different.matches_reference(&reference, &grid, 1e-3));
+        assert!(!Dvh::default().matches_reference(&reference, &grid, 1e-3));
     }
 
     #[test]
-    fn test_dvh_dx_interpolation() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+    #[cfg(feature = "serde")]
+    fn test_dvh_serde_percent_scale() {
+        let unit =
+            r#"{"dose_unit":"Gy","volume_unit":"Percent","d":[0.0,50.0,60.0],"v":[1.0,0.9,0.0]}"#;
+        let hundred = r#"{"dose_unit":"Gy","volume_unit":"Percent","d":[0.0,50.0,60.0],"v":[100.0,90.0,0.0],"percent_scale":"Hundred"}"#;
 
-        let result = dvh.dx(0.9);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 5.0);
+        let mut unit: Dvh = serde_json::from_str(unit).unwrap();
+        let mut hundred: Dvh = serde_json::from_str(hundred).unwrap();
+        assert_eq!(unit.percent_scale, PercentScale::Unit);
+        assert_eq!(hundred.percent_scale, PercentScale::Hundred);
+        unit.dvh_check().unwrap();
+        hundred.dvh_check().unwrap();
+        assert_eq!(hundred.volumes(), &[1.0, 0.9, 0.0]);
+        assert_ulps_eq!(unit.dx(0.95).unwrap(), 25.0, epsilon = 1e-9);
+        assert_ulps_eq!(hundred.dx(0.95).unwrap(), unit.dx(0.95).unwrap());
+
+        // The stored convention is preserved when saving.
+        let serialized = serde_json::to_string(&hundred).unwrap();
+        let raw: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        assert_ulps_eq!(raw["v"][1].as_f64().unwrap(), 90.0);
+        let reloaded: Dvh = serde_json::from_str(&serialized).unwrap();
+        assert_ulps_eq!(reloaded.volumes()[1], 0.9);
     }
 
     #[test]
-    fn test_dvh_dx_below_minimum() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+    fn test_composite_of_differential_beams() {
+        let mut beam1 = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        beam1.kind = DvhKind::Differential;
+        beam1.add_slice(&[5.0, 15.0], &[2.0, 3.0]);
+        let mut beam2 = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        beam2.kind = DvhKind::Differential;
+        beam2.add_slice(&[500.0, 2500.0], &[1.0, 4.0]);
 
-        let result = dvh.dx(0.7);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 10.0);
+        let composite = composite(&[beam1, beam2], &[0.0, 10.0, 20.0, 30.0]).unwrap();
+        assert_eq!(composite.dose_unit, DoseUnit::Gy);
+        assert_eq!(composite.volume_unit, VolumeUnit::Cc);
+        assert_eq!(composite.doses(), &[0.0, 10.0, 20.0, 30.0]);
+        assert_eq!(composite.volumes(), &[10.0, 7.0, 4.0, 0.0]);
+        assert_ulps_eq!(composite.vx(10.0).unwrap(), 7.0);
     }
 
     #[test]
-    fn test_dvh_dx_above_maximum() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+    fn test_composite_invalid_input() {
+        let beam = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        assert!(matches!(composite(&[], &[0.0]), Err(Error::DvhNoData)));
+        assert!(matches!(
+            composite(std::slice::from_ref(&beam), &[0.0, 10.0]),
+            Err(Error::WrongVolumeUnit)
+        ));
+        assert!(matches!(
+            composite(&[beam], &[10.0, 0.0]),
+            Err(Error::InvalidDoseGrid)
+        ));
+        let cumulative = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        assert!(matches!(
+            composite(&[cumulative], &[0.0, 10.0]),
+            Err(Error::WrongDvhKind)
+        ));
+    }
 
-        let result = dvh.dx(1.1);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 0.0);
+    #[test]
+    fn test_dx_vx_exact_stored_point() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 0.3, 0.7, 1.1], &[1.0, 0.7, 0.3, 0.1]);
+        dvh.dvh_check().unwrap();
+        assert_eq!(dvh.dx(0.7).unwrap(), 0.3);
+        assert_eq!(dvh.dx(0.3).unwrap(), 0.7);
+        assert_eq!(dvh.vx(0.7).unwrap(), 0.3);
+        assert_eq!(dvh.vx(1.1).unwrap(), 0.1);
+        assert_eq!(dvh.dx_sorted_queries(&[0.7, 0.3]).unwrap(), vec![0.3, 0.7]);
     }
 
     #[test]
-    fn test_dvh_dx_exact_match() {
+    fn test_uniform_grid() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(5.0, 0.9);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+        dvh.add_slice(&[0.0, 30.0, 60.0], &[1.0, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
 
-        let result = dvh.dx(0.9);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 5.0);
+        let grid = dvh.uniform_grid(0.5).unwrap();
+        assert_eq!(grid.len(), 121);
+        assert_eq!(grid[0], 0.0);
+        assert_eq!(grid[120], 60.0);
+        for w in grid.windows(2) {
+            assert_ulps_eq!(w[1] - w[0], 0.5);
+        }
+
+        assert_eq!(dvh.uniform_grid(25.0).unwrap(), vec![0.0, 25.0, 50.0, 60.0]);
+        assert!(matches!(dvh.uniform_grid(0.0), Err(Error::InvalidDoseGrid)));
+        assert!(matches!(
+            Dvh::default().uniform_grid(1.0),
+            Err(Error::DvhNoData)
+        ));
     }
 
     #[test]
-    fn test_dvh_dx_multiple_points() {
+    fn test_mean_dose_streaming_matches_differential() {
+        let n = 10_000;
+        let doses = (0..n).map(|i| i as f64 * 0.01).collect::<Vec<_>>();
+        let volumes = doses
+            .iter()
+            .map(|&d| 40.0 * (-d / 30.0).exp())
+            .collect::<Vec<_>>();
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&doses, &volumes);
+        dvh.dvh_check().unwrap();
+
+        let streaming = dvh.mean_dose_streaming().unwrap();
+        let differential = dvh.mean_dose().unwrap();
+        assert_ulps_eq!(streaming, differential, epsilon = 1e-9);
+
+        let percent = dvh.to_percent_volume(None).unwrap();
+        assert_ulps_eq!(
+            percent.mean_dose_streaming().unwrap(),
+            streaming,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_round_to() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(5.0, 0.9);
-        dvh.add(10.0, 0.8);
-        dvh.add(15.0, 0.7);
-        dvh.sort();
+        dvh.add_slice(
+            &[0.0, 10.0000000001, 10.0049, 20.123456, 19.999999999],
+            &[1.0, 0.80000000001, 0.8, 0.2000000004, 0.5],
+        );
+        dvh.dvh_check().unwrap();
+
+        dvh.round_to(2, 3);
+        assert_eq!(dvh.doses(), &[0.0, 10.0, 20.0, 20.12]);
+        assert_eq!(dvh.volumes(), &[1.0, 0.8, 0.5, 0.2]);
+        assert!(dvh.is_sorted);
+        assert!(dvh.dvh_check().is_ok());
+        assert_ulps_eq!(dvh.vx(15.0).unwrap(), 0.65);
+
+        let original = dvh.clone();
+        dvh.round_to(400, u32::MAX);
+        assert_eq!(dvh, original);
+    }
+
+    #[test]
+    fn test_from_cumulative_counts() {
+        let dvh =
+            Dvh::from_cumulative_counts(&[0.0, 10.0, 20.0], &[1000, 400, 0], 0.008, DoseUnit::Gy)
+                .unwrap();
+        assert_eq!(dvh.volume_unit, VolumeUnit::Cc);
+        assert_eq!(dvh.dose_unit, DoseUnit::Gy);
+        assert_eq!(dvh.doses(), &[0.0, 10.0, 20.0]);
+        assert_ulps_eq!(dvh.volumes()[0], 8.0);
+        assert_ulps_eq!(dvh.volumes()[1], 3.2);
+        assert_ulps_eq!(dvh.volumes()[2], 0.0);
+        assert_ulps_eq!(dvh.vx(5.0).unwrap(), 5.6);
+
+        assert!(matches!(
+            Dvh::from_cumulative_counts(&[0.0], &[1, 2], 0.008, DoseUnit::Gy),
+            Err(Error::MismatchedLengthDoseVolumeData)
+        ));
+        assert!(matches!(
+            Dvh::from_cumulative_counts(&[10.0, 0.0], &[1, 2], 0.008, DoseUnit::Gy),
+            Err(Error::DvhUnsorted)
+        ));
+        assert!(matches!(
+            Dvh::from_cumulative_counts(&[0.0], &[1], 0.0, DoseUnit::Gy),
+            Err(Error::InvalidVoxelVolume)
+        ));
+        assert!(matches!(
+            Dvh::from_cumulative_counts(&[0.0, f64::NAN], &[1, 0], 0.008, DoseUnit::Gy),
+            Err(Error::NonFiniteValue)
+        ));
+        for voxel_volume_cc in [f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                Dvh::from_cumulative_counts(&[0.0], &[1], voxel_volume_cc, DoseUnit::Gy),
+                Err(Error::NonFiniteValue)
+            ));
+        }
+    }
 
-        // Test interpolation between different segments
-        let result = dvh.dx(0.85);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 7.5);
+    #[test]
+    fn test_max_dose_opt() {
+        assert_eq!(Dvh::default().max_dose_opt(), None);
 
-        let result = dvh.dx(0.79);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 10.5);
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 20.0, 10.0], &[1.0, 0.0, 0.5]);
+        assert_eq!(dvh.max_dose_opt(), Some(20.0));
 
-        let result = dvh.dx(0.71);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 14.5);
+        let mut zero = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        zero.add(0.0, 1.0);
+        assert_eq!(zero.max_dose_opt(), Some(0.0));
     }
 
     #[test]
-    fn test_dvh_vx_negative_dose() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.add(2.0, 0.9);
-        dvh.sort();
+    fn test_differential_counts() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 25.0], &[8.0, 6.0, 2.0, 1.0]);
+        dvh.dvh_check().unwrap();
 
-        let result = dvh.vx(-1.0);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::NegativeDose));
+        let counts = dvh.differential_counts(10.0, 0.5).unwrap();
+        assert_eq!(counts, vec![(5.0, 4), (15.0, 8), (25.0, 4)]);
+        let total = counts.iter().map(|&(_, count)| count).sum::<u64>();
+        assert_eq!(total, 16);
+
+        assert!(matches!(
+            dvh.differential_counts(0.0, 0.5),
+            Err(Error::InvalidDoseGrid)
+        ));
+        assert!(matches!(
+            dvh.differential_counts(10.0, -1.0),
+            Err(Error::InvalidVoxelVolume)
+        ));
     }
 
     #[test]
-    fn test_dvh_vx_empty() {
-        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        let result = dvh.vx(5.0);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::DvhNoData));
+    fn test_volume_ratio() {
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        a.add_slice(&[0.0, 10.0, 20.0, 30.0], &[0.5, 0.4, 0.1, 0.0]);
+        a.dvh_check().unwrap();
+        let mut b = Dvh::new(DoseUnit::CGy, VolumeUnit::Percent);
+        b.add_slice(&[0.0, 1000.0, 2000.0], &[1.0, 0.8, 0.0]);
+        b.dvh_check().unwrap();
+
+        let ratio = volume_ratio(&a, &b, &[0.0, 5.0, 10.0, 25.0]).unwrap();
+        assert_ulps_eq!(ratio[0], 0.5);
+        assert_ulps_eq!(ratio[1], 0.45 / 0.9);
+        assert_ulps_eq!(ratio[2], 0.5);
+        assert!(ratio[3].is_nan());
+
+        let cc = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        assert!(matches!(
+            volume_ratio(&a, &cc, &[0.0]),
+            Err(Error::WrongVolumeUnit)
+        ));
+        let differential = a.to_differential().unwrap();
+        assert!(matches!(
+            volume_ratio(&differential, &differential, &[0.0]),
+            Err(Error::WrongDvhKind)
+        ));
     }
 
     #[test]
-    fn test_dvh_vx_insufficient_data() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.sort();
+    fn test_coverage_levels() {
+        let mut ptv = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        ptv.add_slice(&[0.0, 54.0, 60.0, 66.0], &[100.0, 100.0, 90.0, 0.0]);
+        ptv.dvh_check().unwrap();
 
-        let result = dvh.vx(1.0);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::DvhInsufficientData));
+        let coverage = ptv
+            .coverage_levels(60.0, &[90.0, 95.0, 100.0, 107.0], None)
+            .unwrap();
+        let percents = coverage.iter().map(|&(p, _)| p).collect::<Vec<_>>();
+        assert_eq!(percents, vec![90.0, 95.0, 100.0, 107.0]);
+        assert_ulps_eq!(coverage[0].1, 1.0);
+        assert_ulps_eq!(coverage[1].1, 0.95);
+        assert_ulps_eq!(coverage[2].1, 0.9);
+        assert_ulps_eq!(coverage[3].1, 0.27, epsilon = 1e-12);
+
+        assert!(matches!(
+            ptv.coverage_levels(0.0, &[100.0], None),
+            Err(Error::InvalidPrescription)
+        ));
     }
 
     #[test]
-    fn test_dvh_vx_unsorted() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(1.0, 1.0);
-        dvh.add(2.0, 0.9);
-        // Don't sort
+    #[cfg(feature = "serde")]
+    fn test_dx_verified_detects_stale_sorted_flag() {
+        let json =
+            r#"{"dose_unit":"Gy","volume_unit":"Percent","d":[20.0,0.0,10.0],"v":[0.0,1.0,0.5]}"#;
+        let mut dvh: Dvh = serde_json::from_str(json).unwrap();
+        // The flag is never deserialized; simulate a stale flag on the loaded data.
+        dvh.is_sorted = true;
+        assert!(matches!(dvh.dx_verified(0.5), Err(Error::DvhUnsorted)));
+        assert!(matches!(dvh.vx_verified(5.0), Err(Error::DvhUnsorted)));
 
-        let result = dvh.vx(1.5);
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::DvhUnsorted));
+        dvh.is_sorted = false;
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.dx_verified(0.5).unwrap(), 10.0);
+        assert_ulps_eq!(dvh.vx_verified(5.0).unwrap(), 0.75);
     }
 
     #[test]
-    fn test_dvh_vx_below_minimum() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(5.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+    fn test_weighted_average() {
+        let mut a = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        a.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.8, 0.0]);
+        a.dvh_check().unwrap();
+        let mut b = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        b.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.4, 0.2]);
+        b.dvh_check().unwrap();
 
-        let result = dvh.vx(3.0);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 1.0);
+        let average =
+            weighted_average(&[a.clone(), b.clone()], &[3.0, 1.0], &[0.0, 10.0, 20.0]).unwrap();
+        assert_eq!(average.doses(), &[0.0, 10.0, 20.0]);
+        assert_ulps_eq!(average.volumes()[0], 1.0);
+        assert_ulps_eq!(average.volumes()[1], 0.75 * 0.8 + 0.25 * 0.4);
+        assert_ulps_eq!(average.volumes()[2], 0.25 * 0.2);
+
+        assert!(matches!(
+            weighted_average(&[a.clone(), b.clone()], &[1.0], &[0.0]),
+            Err(Error::InvalidWeights)
+        ));
+        assert!(matches!(
+            weighted_average(&[a, b], &[1.0, -1.0], &[0.0]),
+            Err(Error::InvalidWeights)
+        ));
     }
 
     #[test]
-    fn test_dvh_vx_above_maximum() {
+    fn test_gradient() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(5.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+        dvh.add_slice(&[0.0, 10.0, 10.0, 20.0, 40.0], &[1.0, 0.9, 0.7, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
 
-        let result = dvh.vx(15.0);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 0.8);
+        let (doses, slopes) = dvh.gradient().unwrap();
+        assert_eq!(doses, vec![5.0, 15.0, 30.0]);
+        assert_ulps_eq!(slopes[0], -0.01);
+        assert_ulps_eq!(slopes[1], -0.02);
+        assert_ulps_eq!(slopes[2], -0.025);
+
+        let mut single = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        single.add(0.0, 1.0);
+        assert!(matches!(single.gradient(), Err(Error::DvhInsufficientData)));
     }
 
     #[test]
-    fn test_dvh_vx_exact_match() {
+    fn test_mean_dose_linear_ramp() {
+        // A linear ramp from 100% at 0 Gy to 0% at 60 Gy is a uniform differential
+        // distribution over [0, 60] Gy, so the mean dose is 30 Gy.
+        let doses = (0..=60).map(|i| i as f64).collect::<Vec<_>>();
+        let volumes = doses.iter().map(|&d| 1.0 - d / 60.0).collect::<Vec<_>>();
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(5.0, 0.9);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+        dvh.add_slice(&doses, &volumes);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.mean_dose().unwrap(), 30.0, epsilon = 1e-12);
 
-        let result = dvh.vx(5.0);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 0.9);
+        let cc = dvh.to_absolute_volume(Some(250.0)).unwrap();
+        assert_ulps_eq!(cc.mean_dose().unwrap(), 30.0, epsilon = 1e-12);
     }
 
     #[test]
-    fn test_dvh_vx_interpolation() {
+    fn test_mean_dose_errors() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
         dvh.add(0.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+        assert!(matches!(dvh.mean_dose(), Err(Error::DvhInsufficientData)));
+        dvh.add(10.0, 0.0);
+        assert!(matches!(dvh.mean_dose(), Err(Error::DvhUnsorted)));
+    }
 
-        let result = dvh.vx(5.0);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 0.9);
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialize_lenient_clamps_percent_volumes() {
+        let json = r#"{"dose_unit":"Gy","volume_unit":"Percent","d":[0.0,10.0,20.0],"v":[1.000001,0.5,-0.0000001]}"#;
+        let mut dvh =
+            Dvh::deserialize_lenient(&mut serde_json::Deserializer::from_str(json)).unwrap();
+        assert_eq!(dvh.volumes(), &[1.0, 0.5, 0.0]);
+        assert!(dvh.dvh_check().is_ok());
 
-        let result = dvh.vx(2.0);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 0.96);
+        let strict: Dvh = serde_json::from_str(json).unwrap();
+        assert!(strict.clone().dvh_check().is_err());
 
-        let result = dvh.vx(8.0);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 0.84);
+        let json = r#"{"dose_unit":"Gy","volume_unit":"Percent","d":[0.0,10.0],"v":[1.1,0.0]}"#;
+        assert!(Dvh::deserialize_lenient(&mut serde_json::Deserializer::from_str(json)).is_err());
     }
 
     #[test]
-    fn test_dvh_vx_multiple_points() {
+    fn test_median_dose() {
+        // Asymmetric DVH: half of the volume receives less than 10 Gy, but a tail up to
+        // 60 Gy pulls the mean dose above the median.
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(5.0, 0.9);
-        dvh.add(10.0, 0.8);
-        dvh.add(15.0, 0.7);
-        dvh.sort();
+        dvh.add_slice(&[0.0, 10.0, 60.0], &[1.0, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.median_dose().unwrap(), 10.0);
+        assert_ulps_eq!(dvh.mean_dose().unwrap(), 20.0);
 
-        // Test interpolation between different segments
-        let result = dvh.vx(7.5);
-        assert!(result.is_ok());
-        assert_ulps_eq!(result.unwrap(), 0.85);
+        let mut cc = dvh.to_absolute_volume(Some(80.0)).unwrap();
+        assert_ulps_eq!(cc.median_dose().unwrap(), 10.0);
+        // Only 80 cc of a 160 cc structure are covered by the DVH, half of the structure
+        // receives at least 0 Gy.
+        cc.reference_volume_cc = Some(160.0);
+        assert_ulps_eq!(cc.median_dose().unwrap(), 0.0);
+        assert!(matches!(
+            Dvh::default().median_dose(),
+            Err(Error::DvhNoData)
+        ));
     }
 
     #[test]
-    #[cfg(feature = "serde")]
-    fn test_dvh_serde() {
-        let mut dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
-        dvh.add(0.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.sort();
+    fn test_import_increasing_cumulative() {
+        let dvh = Dvh::import_increasing_cumulative(
+            &[0.0, 10.0, 20.0, 30.0],
+            &[0.0, 5.0, 15.0, 20.0],
+            20.0,
+            DoseUnit::Gy,
+            VolumeUnit::Cc,
+        )
+        .unwrap();
+        assert_eq!(dvh.volumes(), &[20.0, 15.0, 5.0, 0.0]);
+        assert_ulps_eq!(dvh.vx(15.0).unwrap(), 10.0);
+        assert_ulps_eq!(dvh.vx(30.0).unwrap(), 0.0);
 
-        let serialized = serde_json::to_string(&dvh).unwrap();
-        let mut deserialized: Dvh = serde_json::from_str(&serialized).unwrap();
-        deserialized.sort();
+        assert!(matches!(
+            Dvh::import_increasing_cumulative(
+                &[0.0, 10.0],
+                &[0.5, 0.2],
+                1.0,
+                DoseUnit::Gy,
+                VolumeUnit::Percent,
+            ),
+            Err(Error::NonMonotonicVolume)
+        ));
+    }
 
-        assert_eq!(deserialized.dose_unit, DoseUnit::CGy);
-        assert_eq!(deserialized.volume_unit, VolumeUnit::Cc);
-        assert_eq!(deserialized.len(), 2);
-        assert_ulps_eq!(deserialized.dx(0.9).unwrap(), 5.0);
+    #[test]
+    fn test_min_dose_skips_zero_volume_bins() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 1.0, 2.0, 3.0, 4.0], &[0.0, 0.0, 2.0, 5.0, 1.0]);
+        assert_ulps_eq!(dvh.min_dose(), 2.0);
+        assert_ulps_eq!(Dvh::default().min_dose(), 0.0);
     }
 
     #[test]
-    fn test_dvh_check_mismatched_lengths() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.d = vec![1.0, 2.0, 3.0];
-        dvh.v = vec![1.0, 0.9];
+    fn test_coverage_crossing() {
+        let mut ptv = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        ptv.add_slice(&[0.0, 57.0, 60.0, 63.0], &[1.0, 0.98, 0.9, 0.0]);
+        ptv.dvh_check().unwrap();
 
-        let result = dvh.dvh_check();
-        assert!(result.is_err());
+        let (dose, interpolated) = ptv.coverage_crossing(0.95).unwrap();
+        assert_ulps_eq!(dose, 57.0 + 3.0 * 0.03 / 0.08, epsilon = 1e-12);
+        assert!(interpolated);
+        assert_eq!(ptv.coverage_crossing(0.9).unwrap(), (60.0, false));
+
+        let cc = ptv.to_absolute_volume(Some(200.0)).unwrap();
+        let (dose, interpolated) = cc.coverage_crossing(0.95).unwrap();
+        assert_ulps_eq!(dose, 57.0 + 3.0 * 0.03 / 0.08, epsilon = 1e-12);
+        assert!(interpolated);
         assert!(matches!(
-            result.unwrap_err(),
-            Error::MismatchedLengthDoseVolumeData
+            ptv.coverage_crossing(1.5),
+            Err(Error::PercentVolumeOutOfRange)
         ));
+
+        // Only 98% of the structure is covered by the DVH: full coverage is clamped to
+        // the lowest dose point rather than interpolated.
+        let mut partial = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        partial.add_slice(&[0.0, 60.0, 63.0], &[0.98, 0.9, 0.1]);
+        partial.dvh_check().unwrap();
+        assert_eq!(partial.coverage_crossing(1.0).unwrap(), (0.0, false));
+        assert_eq!(partial.coverage_crossing(0.05).unwrap(), (63.0, false));
     }
 
     #[test]
-    fn test_dvh_check_negative_dose() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.d = vec![1.0, -2.0, 3.0];
-        dvh.v = vec![1.0, 0.9, 0.8];
+    fn test_vx_abs_and_vx_percent_gy() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 20.0, 40.0], &[80.0, 40.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.vx_abs(20.0).unwrap(), 40.0);
+        assert_ulps_eq!(dvh.vx_percent(20.0).unwrap(), 0.5);
 
-        let result = dvh.dvh_check();
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::NegativeDose));
+        dvh.reference_volume_cc = Some(160.0);
+        assert_ulps_eq!(dvh.vx_percent(20.0).unwrap(), 0.25);
     }
 
     #[test]
-    fn test_dvh_check_negative_volume() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.d = vec![1.0, 2.0, 3.0];
-        dvh.v = vec![1.0, -0.9, 0.8];
-
-        let result = dvh.dvh_check();
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), Error::NegativeVolume));
+    fn test_vx_abs_and_vx_percent_cgy() {
+        let mut dvh = Dvh::new(DoseUnit::CGy, VolumeUnit::Percent);
+        dvh.add_slice(&[0.0, 2000.0, 4000.0], &[1.0, 0.3, 0.0]);
+        dvh.dvh_check().unwrap();
+        assert_ulps_eq!(dvh.vx_abs(2000.0).unwrap(), 0.3);
+        assert_ulps_eq!(dvh.vx_percent(2000.0).unwrap(), 0.3);
+        assert_ulps_eq!(dvh.vx_abs(3000.0).unwrap(), 0.15);
     }
 
     #[test]
-    fn test_dvh_check_percent_volume_out_of_range() {
+    fn test_renormalize_to() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.d = vec![1.0, 2.0, 3.0];
-        dvh.v = vec![1.0, 1.5, 0.8];
+        dvh.add_slice(&[0.0, 50.0, 55.0, 60.0], &[1.0, 1.0, 0.5, 0.0]);
+        dvh.dvh_check().unwrap();
 
-        let result = dvh.dvh_check();
-        assert!(result.is_err());
+        let renormalized = dvh.renormalize_to(0.5, 60.0).unwrap();
+        let scale = 60.0 / 55.0;
+        for (a, b) in renormalized.doses().iter().zip(dvh.doses()) {
+            assert_ulps_eq!(*a, b * scale);
+        }
+        assert_ulps_eq!(renormalized.dx(0.5).unwrap(), 60.0);
+
+        let mut zero = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        zero.add_slice(&[0.0, 10.0], &[0.5, 0.0]);
+        zero.dvh_check().unwrap();
         assert!(matches!(
-            result.unwrap_err(),
-            Error::PercentVolumeOutOfRange
+            zero.renormalize_to(0.5, 60.0),
+            Err(Error::InvalidReferenceDose)
         ));
     }
 
     #[test]
-    fn test_dvh_check_success_with_sorting() {
+    fn test_geud_limits() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(10.0, 0.8);
-        dvh.add(5.0, 1.0);
-        dvh.add(15.0, 0.5);
+        dvh.add_slice(&[10.0, 10.0, 20.0, 20.0], &[1.0, 0.5, 0.5, 0.2]);
+        dvh.dvh_check().unwrap();
 
-        let result = dvh.dvh_check();
-        assert!(result.is_ok());
-        assert!(dvh.is_sorted);
-        assert_eq!(dvh.doses(), vec![5.0, 10.0, 15.0]);
-        assert_eq!(dvh.volumes(), vec![1.0, 0.8, 0.5]);
+        assert_ulps_eq!(dvh.geud(200.0).unwrap(), dvh.max_dose(), epsilon = 0.1);
+        assert_ulps_eq!(dvh.geud(-200.0).unwrap(), dvh.min_dose(), epsilon = 0.1);
+        assert_ulps_eq!(dvh.geud(1.0).unwrap(), dvh.mean_dose().unwrap());
+        // Geometric mean: 10^0.5 * 20^0.5
+        assert_ulps_eq!(dvh.geud(0.0).unwrap(), 200f64.sqrt(), epsilon = 1e-12);
+
+        let mut single = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        single.add(10.0, 1.0);
+        assert!(matches!(single.geud(1.0), Err(Error::DvhInsufficientData)));
     }
 
     #[test]
-    fn test_dvh_check_empty() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+    fn test_weighted_average_rejects_differential_dvh() {
+        let mut cumulative = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        cumulative.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        cumulative.dvh_check().unwrap();
+        let mut differential = cumulative.clone();
+        differential.kind = DvhKind::Differential;
 
-        let result = dvh.dvh_check();
-        assert!(result.is_ok());
+        assert!(matches!(
+            weighted_average(
+                &[cumulative.clone(), differential.clone()],
+                &[1.0, 1.0],
+                &[0.0]
+            ),
+            Err(Error::WrongDvhKind)
+        ));
+        assert!(matches!(
+            volume_ratio(&cumulative, &differential, &[0.0]),
+            Err(Error::WrongDvhKind)
+        ));
     }
 
     #[test]
-    fn test_dvh_check_already_sorted() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(5.0, 1.0);
-        dvh.add(10.0, 0.8);
-        dvh.add(15.0, 0.5);
-        dvh.sort();
+    fn test_to_differential() {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0, 30.0], &[50.0, 40.0, 15.0, 5.0]);
+        dvh.dvh_check().unwrap();
 
-        let result = dvh.dvh_check();
-        assert!(result.is_ok());
-        assert!(dvh.is_sorted);
-        assert_eq!(dvh.doses(), vec![5.0, 10.0, 15.0]);
-        assert_eq!(dvh.volumes(), vec![1.0, 0.8, 0.5]);
+        let differential = dvh.to_differential().unwrap();
+        assert_eq!(differential.kind, DvhKind::Differential);
+        assert_eq!(differential.dose_unit, DoseUnit::Gy);
+        assert_eq!(differential.doses(), &[5.0, 15.0, 25.0, 30.0]);
+        assert_eq!(differential.volumes(), &[10.0, 25.0, 10.0, 5.0]);
+        assert_ulps_eq!(differential.volumes().iter().sum::<f64>(), 50.0);
+
+        assert!(matches!(
+            differential.to_differential(),
+            Err(Error::WrongDvhKind)
+        ));
+        let mut unsorted = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        unsorted.add_slice(&[10.0, 0.0], &[0.0, 1.0]);
+        assert!(matches!(
+            unsorted.to_differential(),
+            Err(Error::DvhUnsorted)
+        ));
     }
 
     #[test]
-    fn test_max_dose_empty() {
-        let dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        assert_eq!(dvh.max_dose(), 0.0);
+    fn test_dose_entropy() {
+        let mut two_bins = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        two_bins.add_slice(&[0.0, 10.0, 20.0], &[1.0, 0.5, 0.0]);
+        two_bins.dvh_check().unwrap();
+        assert_ulps_eq!(two_bins.dose_entropy(10.0).unwrap(), 2f64.ln());
+
+        let mut single_bin = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        single_bin.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        single_bin.dvh_check().unwrap();
+        assert_ulps_eq!(single_bin.dose_entropy(20.0).unwrap(), 0.0);
+
+        assert!(matches!(
+            two_bins.dose_entropy(0.0),
+            Err(Error::InvalidDoseGrid)
+        ));
     }
 
     #[test]
-    fn test_max_dose_single_value() {
+    fn test_to_cumulative_round_trip() {
+        let doses = (0..=100).map(|i| i as f64 * 0.5).collect::<Vec<_>>();
+        let volumes = doses
+            .iter()
+            .map(|&d| 1.0 / (1.0 + (0.3 * (d - 30.0)).exp()))
+            .collect::<Vec<_>>();
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(42.5, 1.0);
-        assert_ulps_eq!(dvh.max_dose(), 42.5);
+        dvh.add_slice(&doses, &volumes);
+        dvh.dvh_check().unwrap();
+
+        let round_trip = dvh.to_differential().unwrap().to_cumulative().unwrap();
+        assert_eq!(round_trip.kind, DvhKind::Cumulative);
+        assert_eq!(round_trip.len(), dvh.len());
+        for (a, b) in round_trip.volumes().iter().zip(dvh.volumes()) {
+            assert_ulps_eq!(*a, *b, epsilon = 1e-12);
+        }
+        assert_eq!(round_trip.doses(), dvh.doses());
+        assert!(round_trip.volumes().windows(2).all(|w| w[0] >= w[1]));
+
+        let mut cc = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        cc.add_slice(&[0.0, 10.0, 20.0, 30.0], &[50.0, 40.0, 15.0, 5.0]);
+        cc.dvh_check().unwrap();
+        let round_trip = cc.to_differential().unwrap().to_cumulative().unwrap();
+        assert_eq!(round_trip.doses(), cc.doses());
+        assert_eq!(round_trip.volumes(), cc.volumes());
+        assert_ulps_eq!(round_trip.vx(15.0).unwrap(), 27.5);
+
+        assert!(matches!(dvh.to_cumulative(), Err(Error::WrongDvhKind)));
     }
 
     #[test]
-    fn test_max_dose_multiple_values() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(10.0, 1.0);
-        dvh.add(25.0, 0.8);
-        dvh.add(15.0, 0.9);
-        dvh.add(50.0, 0.5);
-        dvh.add(30.0, 0.7);
-        assert_ulps_eq!(dvh.max_dose(), 50.0);
+    fn test_per_structure_prescription() {
+        // SIB plan: the boost target is prescribed 66 Gy, the elective target 54 Gy.
+        let mut boost = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        boost.add_slice(&[0.0, 62.7, 66.0, 70.0], &[1.0, 1.0, 0.5, 0.0]);
+        boost.dvh_check().unwrap();
+        boost.prescription_dose = Some(66.0);
+        let mut elective = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        elective.add_slice(&[0.0, 51.3, 54.0, 58.0], &[1.0, 1.0, 0.5, 0.0]);
+        elective.dvh_check().unwrap();
+        elective.prescription_dose = Some(54.0);
+
+        let plan_prescription = 60.0;
+        for target in [&boost, &elective] {
+            let coverage = target
+                .coverage_levels(plan_prescription, &[95.0, 100.0], None)
+                .unwrap();
+            assert_ulps_eq!(coverage[0].1, 1.0);
+            assert_ulps_eq!(coverage[1].1, 0.5);
+        }
+        let (doses, _) = elective.normalized_overlay(plan_prescription).unwrap();
+        assert_ulps_eq!(doses[2], 100.0);
+
+        elective.prescription_dose = None;
+        let coverage = elective
+            .coverage_levels(plan_prescription, &[100.0], None)
+            .unwrap();
+        assert_ulps_eq!(coverage[0].1, 0.0);
     }
 
     #[test]
-    fn test_max_dose_with_negative_values() {
+    fn test_to_dose_unit() {
         let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.d = vec![-5.0, -10.0, -2.0];
-        dvh.v = vec![1.0, 0.8, 0.9];
-        assert_eq!(dvh.max_dose(), 0.0);
+        dvh.add_slice(&[0.0, 25.0, 50.0], &[1.0, 0.6, 0.0]);
+        dvh.dvh_check().unwrap();
+        dvh.prescription_dose = Some(50.0);
+
+        let cgy = dvh.to_dose_unit(DoseUnit::CGy);
+        assert_eq!(cgy.dose_unit, DoseUnit::CGy);
+        assert_eq!(cgy.doses(), &[0.0, 2500.0, 5000.0]);
+        assert_eq!(cgy.volumes(), dvh.volumes());
+        assert_eq!(cgy.prescription_dose, Some(5000.0));
+        assert_ulps_eq!(cgy.dx(0.3).unwrap(), dvh.dx(0.3).unwrap() * 100.0);
+        assert_ulps_eq!(cgy.vx(1000.0).unwrap(), dvh.vx(10.0).unwrap());
+
+        let gy = cgy.to_dose_unit(DoseUnit::Gy);
+        assert_eq!(gy.doses(), dvh.doses());
+        assert_eq!(dvh.to_dose_unit(DoseUnit::Gy), dvh);
     }
 
-    #[test]
-    fn test_max_dose_all_zeros() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(0.0, 1.0);
-        dvh.add(0.0, 0.8);
-        dvh.add(0.0, 0.5);
-        assert_eq!(dvh.max_dose(), 0.0);
+    /// Reference Dx implementation with the linear scan that preceded the binary search.
+    fn linear_dx(dvh: &Dvh, volume: f64) -> f64 {
+        let n = dvh.v.len();
+        if volume <= dvh.v[n - 1] {
+            return dvh.d[n - 1];
+        }
+        if let Some(i) = dvh
+            .v
+            .iter()
+            .rposition(|&v| ulps_eq(v, volume, EXACT_MATCH_ULPS))
+        {
+            return dvh.d[i];
+        }
+        let (mut x0, mut y0) = (dvh.v[n - 1], dvh.d[n - 1]);
+        for (&x1, &y1) in dvh.v.iter().rev().zip(dvh.d.iter().rev()).skip(1) {
+            if volume >= x0 && volume <= x1 {
+                return linear_interpolation(volume, x0, x1, y0, y1);
+            }
+            (x0, y0) = (x1, y1);
+        }
+        y0
+    }
+
+    /// Reference Vx implementation with the linear scan that preceded the binary search.
+    fn linear_vx(dvh: &Dvh, dose: f64) -> f64 {
+        let n = dvh.d.len();
+        if dose <= dvh.d[0] {
+            return dvh.v[0];
+        }
+        if let Some(i) = dvh
+            .d
+            .iter()
+            .position(|&d| ulps_eq(d, dose, EXACT_MATCH_ULPS))
+        {
+            return dvh.v[i];
+        }
+        for i in 1..n {
+            if dose >= dvh.d[i - 1] && dose <= dvh.d[i] {
+                return linear_interpolation(dose, dvh.d[i - 1], dvh.d[i], dvh.v[i - 1], dvh.v[i]);
+            }
+        }
+        dvh.v[n - 1]
     }
 
     #[test]
-    fn test_max_dose_unsorted() {
-        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
-        dvh.add(30.0, 0.7);
-        dvh.add(10.0, 1.0);
-        dvh.add(50.0, 0.5);
-        dvh.add(25.0, 0.8);
-        assert_ulps_eq!(dvh.max_dose(), 50.0);
+    fn test_dx_vx_binary_search_matches_linear_scan() {
+        // Small linear congruential generator to keep the test deterministic.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        for _ in 0..200 {
+            let n = 2 + (random() * 50.0) as usize;
+            let mut doses = vec![0.0];
+            let mut volumes = vec![1.0];
+            for _ in 1..n {
+                // Repeat doses and volumes now and then to exercise plateaus and ties.
+                let step = if random() < 0.1 { 0.0 } else { random() * 5.0 };
+                let drop = if random() < 0.2 { 0.0 } else { random() * 0.1 };
+                doses.push(doses.last().unwrap() + step);
+                volumes.push((volumes.last().unwrap() - drop).max(0.0));
+            }
+            let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+            assert!(dvh.add_slice(&doses, &volumes));
+            dvh.dvh_check().unwrap();
+            let max_dose = dvh.max_dose();
+
+            for k in 0..50 {
+                let volume = random() * 1.1;
+                assert_ulps_eq!(dvh.dx(volume).unwrap(), linear_dx(&dvh, volume));
+                let dose = random() * (max_dose + 5.0);
+                assert_ulps_eq!(dvh.vx(dose).unwrap(), linear_vx(&dvh, dose));
+                // Query exactly on the data points as well.
+                let i = k % dvh.len();
+                assert_ulps_eq!(dvh.dx(dvh.v[i]).unwrap(), linear_dx(&dvh, dvh.v[i]));
+                assert_ulps_eq!(dvh.vx(dvh.d[i]).unwrap(), linear_vx(&dvh, dvh.d[i]));
+            }
+        }
     }
 }
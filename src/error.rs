@@ -19,6 +19,120 @@ pub enum Error {
     PercentVolumeOutOfRange,
     #[error("The length of the dose and volume arrays is different.")]
     MismatchedLengthDoseVolumeData,
+    #[error("The total structure volume must be positive.")]
+    InvalidTotalVolume,
+    #[error("The DVH has the wrong volume unit for this operation.")]
+    WrongVolumeUnit,
+    #[error("The DVH is of the wrong kind (cumulative or differential) for this operation.")]
+    WrongDvhKind,
+    #[error("The batched queries are not sorted in the required order.")]
+    UnsortedQueries,
+    #[error("The dose grid is invalid.")]
+    InvalidDoseGrid,
+    #[error("The percent volumes appear to be in the range [0.0, 100.0]; divide them by 100.")]
+    LikelyPercent100,
+    #[error("The prescription dose must be positive.")]
+    InvalidPrescription,
+    #[error("The dose at the reference volume must be positive.")]
+    InvalidReferenceDose,
+    #[error("The dose window is invalid or contains no volume.")]
+    InvalidDoseWindow,
+    #[error("The total structure volume is required to convert between percent and cc volumes.")]
+    VolumeUnitConversionRequired,
+    #[error("The index range is out of bounds of the DVH data.")]
+    IndexOutOfRange,
+    #[error("The number of fractions must be positive.")]
+    InvalidFractionation,
+    #[error("The DVH does not start at dose 0; the structure volume cannot be determined.")]
+    MissingZeroDosePoint,
+    #[error("The volume {value} at index {index} is outside the plausible range.")]
+    ImplausibleVolume { index: usize, value: f64 },
+    #[error("The volumes are not monotonic in the expected direction.")]
+    NonMonotonicVolume,
+    #[error("The weights must be non-negative, not all zero and one per DVH.")]
+    InvalidWeights,
+    #[error("The voxel volume must be positive.")]
+    InvalidVoxelVolume,
+    #[error("The bin width must be positive and finite.")]
+    InvalidBinWidth,
+    #[error("The DVHs have different dose or volume units.")]
+    IncompatibleDvhUnits,
+    #[error("A NaN or infinite dose or volume value is not valid.")]
+    NonFiniteValue,
+    #[error("The volume fraction is out of range [0.0, 1.0].")]
+    FractionOutOfRange,
+    #[error("The dose and volume tolerances must be positive.")]
+    InvalidTolerance,
+    #[error("The alpha/beta ratio and the dose per fraction must be positive and finite.")]
+    InvalidRadiobiologyParameter,
+    #[error("The structure '{structure}' was not found.")]
+    StructureNotFound { structure: String },
+    #[error("The structure '{structure}' already exists.")]
+    DuplicateStructure { structure: String },
+    #[error("The plan '{plan_id}' already exists.")]
+    DuplicatePlanId { plan_id: String },
+    #[error("Error in structure '{structure}': {source}")]
+    StructureError {
+        structure: String,
+        #[source]
+        source: Box<Error>,
+    },
+    #[error("Error in plan '{plan_id}': {source}")]
+    PlanError {
+        plan_id: String,
+        #[source]
+        source: Box<Error>,
+    },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid numeric data on line {line}.")]
+    ParseError { line: usize },
+    #[error("{msg}: {source}")]
+    Context {
+        msg: String,
+        #[source]
+        source: Box<Error>,
+    },
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+impl Error {
+    /// Wraps the error with a message describing the context in which it occurred.
+    ///
+    /// The original error is kept as the source of the new error, so the error chain
+    /// is preserved.
+    ///
+    /// # Parameters
+    /// - `msg`: The context message
+    ///
+    /// # Returns
+    /// An `Error::Context` wrapping `self`
+    pub fn context(self, msg: impl Into<String>) -> Error {
+        Error::Context {
+            msg: msg.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_context() {
+        let err = Error::DvhNoData.context("loading PTV");
+        assert_eq!(err.to_string(), "loading PTV: DVH data is empty.");
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "DVH data is empty.");
+        assert!(source.source().is_none());
+
+        let err = err.context("evaluating plan");
+        assert_eq!(
+            err.to_string(),
+            "evaluating plan: loading PTV: DVH data is empty."
+        );
+    }
+}
\ No newline at end of file
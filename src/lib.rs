@@ -1,13 +1,21 @@
+mod constraint;
 mod dvh;
 mod error;
+mod metric;
 mod name;
 mod patient;
 mod plan;
+mod report;
+mod roi;
 mod traits;
 
+pub use constraint::*;
 pub use dvh::*;
 pub use error::*;
+pub use metric::*;
 pub use name::*;
 pub use patient::*;
 pub use plan::*;
+pub use report::*;
+pub use roi::*;
 pub use traits::*;
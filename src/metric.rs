@@ -0,0 +1,112 @@
+//! Dose-volume metrics.
+//!
+//! This module provides the [`Metric`] enum describing the scalar quantities
+//! commonly reported for a dose-volume histogram.
+
+use crate::{Dvh, MaxDose, VolumeUnit};
+
+/// Represents a scalar quantity derived from a dose-volume histogram.
+///
+/// Volume arguments and results are expressed as a fraction of the structure volume,
+/// regardless of the [VolumeUnit] of the DVH, so that metric values can be compared
+/// across structures.
+///
+/// # Variants
+/// - `Dx`: Minimum dose received by the given volume fraction [0.0, 1.0] of the structure
+/// - `Vx`: Volume fraction [0.0, 1.0] of the structure receiving at least the given dose
+/// - `MaxDose`: Maximum dose in the structure
+/// - `MeanDose`: Volume-weighted mean dose in the structure
+/// - `HomogeneityIndex`: The homogeneity index `(D2 - D98) / D50` (ICRU 83). The result
+///   is not finite if D50 is 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Metric {
+    Dx(f64),
+    Vx(f64),
+    MaxDose,
+    MeanDose,
+    HomogeneityIndex,
+}
+
+impl Metric {
+    /// Evaluates the metric on a DVH.
+    ///
+    /// # Parameters
+    /// - `dvh`: The DVH to evaluate the metric on
+    /// - `structure_volume_cc`: The total structure volume in cc, used to convert between
+    ///   volume fractions and [Cc](VolumeUnit::Cc) volumes. If `None`, the reference
+    ///   volume of the DVH is used, or the largest volume in the DVH if that is not set.
+    ///
+    /// # Returns
+    /// The value of the metric
+    ///
+    /// # Errors
+    /// - `Error::InvalidTotalVolume`: If the structure volume of a [Cc](VolumeUnit::Cc) DVH
+    ///   is not positive
+    /// - Any error returned by [`Dvh::dx`], [`Dvh::vx`] or [`Dvh::mean_dose`]
+    pub fn evaluate(&self, dvh: &Dvh, structure_volume_cc: Option<f64>) -> crate::Result<f64> {
+        match *self {
+            Metric::Dx(fraction) => match dvh.volume_unit {
+                VolumeUnit::Percent => dvh.dx(fraction),
+                VolumeUnit::Cc => dvh.dx(fraction * dvh.resolve_total_volume(structure_volume_cc)?),
+            },
+            Metric::Vx(dose) => match dvh.volume_unit {
+                VolumeUnit::Percent => dvh.vx(dose),
+                VolumeUnit::Cc => {
+                    Ok(dvh.vx(dose)? / dvh.resolve_total_volume(structure_volume_cc)?)
+                }
+            },
+            Metric::MaxDose => Ok(dvh.max_dose()),
+            Metric::MeanDose => dvh.mean_dose(),
+            Metric::HomogeneityIndex => {
+                let d2 = Metric::Dx(0.02).evaluate(dvh, structure_volume_cc)?;
+                let d50 = Metric::Dx(0.5).evaluate(dvh, structure_volume_cc)?;
+                let d98 = Metric::Dx(0.98).evaluate(dvh, structure_volume_cc)?;
+                Ok((d2 - d98) / d50)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DoseUnit, DvhCheck, Error};
+    use approx::assert_ulps_eq;
+
+    fn cc_dvh() -> Dvh {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        dvh.add_slice(&[0.0, 10.0, 20.0], &[40.0, 20.0, 0.0]);
+        dvh.dvh_check().unwrap();
+        dvh
+    }
+
+    #[test]
+    fn test_metric_cc_dvh_uses_fractions() {
+        let dvh = cc_dvh();
+        assert_ulps_eq!(Metric::Dx(0.5).evaluate(&dvh, None).unwrap(), 10.0);
+        assert_ulps_eq!(Metric::Vx(10.0).evaluate(&dvh, None).unwrap(), 0.5);
+        assert_ulps_eq!(Metric::Vx(10.0).evaluate(&dvh, Some(80.0)).unwrap(), 0.25);
+        assert_ulps_eq!(Metric::MaxDose.evaluate(&dvh, None).unwrap(), 20.0);
+    }
+
+    #[test]
+    fn test_metric_invalid_structure_volume() {
+        let dvh = cc_dvh();
+        let result = Metric::Vx(10.0).evaluate(&dvh, Some(0.0));
+        assert!(matches!(result, Err(Error::InvalidTotalVolume)));
+    }
+
+    #[test]
+    fn test_metric_mean_dose_and_homogeneity_index() {
+        let dvh = cc_dvh();
+        // Uniform differential over [0, 20] Gy.
+        assert_ulps_eq!(Metric::MeanDose.evaluate(&dvh, None).unwrap(), 10.0);
+        // D2 = 19.6, D50 = 10.0, D98 = 0.4
+        assert_ulps_eq!(
+            Metric::HomogeneityIndex.evaluate(&dvh, None).unwrap(),
+            1.92,
+            epsilon = 1e-12
+        );
+    }
+}
@@ -24,10 +24,7 @@ pub struct Name {
 
 impl Name {
     pub fn from_dicom(s: &str) -> Self {
-        let parts = s
-            .trim()
-            .split(|c| c == '^' || c == '\\')
-            .collect::<Vec<_>>();
+        let parts = s.trim().split(['^', '\\']).collect::<Vec<_>>();
         let n = parts.len();
         Self {
             last: if n > 0 {
@@ -57,6 +54,67 @@ impl Name {
             },
         }
     }
+
+    /// Parses a name formatted as "Last, First Middle", e.g. "Smith, John M.".
+    ///
+    /// The text before the first comma is the last name. The remainder is split on
+    /// whitespace into the first name and the middle name, which receives all remaining
+    /// words. Without a comma, the whole text is the last name. All components are
+    /// trimmed.
+    pub fn from_comma_separated(s: &str) -> Self {
+        let (last, rest) = s.split_once(',').unwrap_or((s, ""));
+        let mut words = rest.split_whitespace();
+        let first = words.next().unwrap_or_default().to_string();
+        let middle = words.collect::<Vec<_>>().join(" ");
+        Self {
+            last: last.trim().to_string(),
+            first,
+            middle,
+            ..Default::default()
+        }
+    }
+
+    /// Formats the name as a DICOM person name (PN), e.g. "Doe^John".
+    ///
+    /// The components are joined with `^` in the order last, first, middle, prefix and
+    /// suffix. Trailing empty components are omitted. This is the inverse of
+    /// [`Name::from_dicom`].
+    pub fn to_dicom(&self) -> String {
+        let parts = [
+            &self.last,
+            &self.first,
+            &self.middle,
+            &self.prefix,
+            &self.suffix,
+        ];
+        let n = parts
+            .iter()
+            .rposition(|part| !part.is_empty())
+            .map_or(0, |i| i + 1);
+        parts[..n]
+            .iter()
+            .map(|part| part.as_str())
+            .collect::<Vec<_>>()
+            .join("^")
+    }
+}
+
+impl std::fmt::Display for Name {
+    /// Formats the name for display as "prefix first middle last, suffix", omitting
+    /// empty components.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = [&self.prefix, &self.first, &self.middle, &self.last]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| part.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match (name.is_empty(), self.suffix.is_empty()) {
+            (_, true) => write!(f, "{name}"),
+            (true, false) => write!(f, "{}", self.suffix),
+            (false, false) => write!(f, "{name}, {}", self.suffix),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -152,4 +210,74 @@ mod tests {
         assert_eq!(name.prefix, "");
         assert_eq!(name.suffix, "");
     }
+
+    #[test]
+    fn test_to_dicom_round_trip() {
+        let dicom = "Doe^John^Michael^Dr.^Jr.";
+        assert_eq!(Name::from_dicom(dicom).to_dicom(), dicom);
+        assert_eq!(Name::from_dicom("Doe^^Michael").to_dicom(), "Doe^^Michael");
+        assert_eq!(Name::from_dicom("Doe^John^^^").to_dicom(), "Doe^John");
+    }
+
+    #[test]
+    fn test_to_dicom_last_only() {
+        let name = Name {
+            last: "Doe".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(name.to_dicom(), "Doe");
+    }
+
+    #[test]
+    fn test_to_dicom_empty() {
+        assert_eq!(Name::default().to_dicom(), "");
+    }
+
+    #[test]
+    fn test_display_empty() {
+        assert_eq!(Name::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_display_first_and_last() {
+        let name = Name::from_dicom("Doe^John");
+        assert_eq!(name.to_string(), "John Doe");
+        let name = Name::from_dicom("Doe^John^^^Jr.");
+        assert_eq!(name.to_string(), "John Doe, Jr.");
+    }
+
+    #[test]
+    fn test_display_full_name() {
+        let name = Name::from_dicom("Doe^John^Michael^Dr.^Jr.");
+        assert_eq!(name.to_string(), "Dr. John Michael Doe, Jr.");
+    }
+
+    #[test]
+    fn test_from_comma_separated_last_and_first() {
+        let name = Name::from_comma_separated("Smith, John");
+        assert_eq!(name.last, "Smith");
+        assert_eq!(name.first, "John");
+        assert_eq!(name.middle, "");
+    }
+
+    #[test]
+    fn test_from_comma_separated_with_middle() {
+        let name = Name::from_comma_separated("  Smith ,  John   M. ");
+        assert_eq!(name.last, "Smith");
+        assert_eq!(name.first, "John");
+        assert_eq!(name.middle, "M.");
+    }
+
+    #[test]
+    fn test_from_comma_separated_no_comma() {
+        let name = Name::from_comma_separated(" John Smith ");
+        assert_eq!(name.last, "John Smith");
+        assert_eq!(name.first, "");
+        assert_eq!(name.middle, "");
+    }
+
+    #[test]
+    fn test_from_comma_separated_empty_string() {
+        assert_eq!(Name::from_comma_separated(""), Name::default());
+    }
 }
@@ -4,6 +4,8 @@
 //! in radiation therapy contexts, including patient identification and associated
 //! treatment plans.
 
+use crate::Dvh;
+use crate::Error;
 use crate::name::Name;
 use crate::plan::Plan;
 use crate::traits::DvhCheck;
@@ -23,6 +25,104 @@ pub struct Patient {
     pub plans: Vec<Plan>,
 }
 
+impl Patient {
+    /// Finds a plan by its identifier.
+    ///
+    /// If several plans share the identifier, the first one wins.
+    ///
+    /// # Parameters
+    /// - `id`: The plan identifier
+    ///
+    /// # Returns
+    /// The plan with the identifier, or `None` if the patient has no such plan
+    pub fn get_plan(&self, id: &str) -> Option<&Plan> {
+        self.plans.iter().find(|plan| plan.id == id)
+    }
+
+    /// Finds a plan by its identifier for modification.
+    ///
+    /// If several plans share the identifier, the first one wins.
+    ///
+    /// # Parameters
+    /// - `id`: The plan identifier
+    ///
+    /// # Returns
+    /// The plan with the identifier, or `None` if the patient has no such plan
+    pub fn get_plan_mut(&mut self, id: &str) -> Option<&mut Plan> {
+        self.plans.iter_mut().find(|plan| plan.id == id)
+    }
+
+    /// Finds the DVH of a structure in a plan.
+    ///
+    /// The plan is looked up with [`Patient::get_plan`], so the first plan wins if ids
+    /// are duplicated.
+    ///
+    /// # Parameters
+    /// - `plan_id`: The plan identifier
+    /// - `structure`: The structure name
+    ///
+    /// # Returns
+    /// The DVH of the structure, or `None` if either the plan or the structure is missing
+    pub fn find_dvh(&self, plan_id: &str, structure: &str) -> Option<&Dvh> {
+        self.get_plan(plan_id)?.get_dvh(structure)
+    }
+
+    /// Returns an iterator over the DVHs of all plans.
+    ///
+    /// Plans are visited in order and the structures of a plan in alphabetical order.
+    ///
+    /// # Returns
+    /// An iterator over the (plan id, structure name, DVH) tuples
+    pub fn all_dvhs(&self) -> impl Iterator<Item = (&str, &str, &Dvh)> {
+        self.plans.iter().flat_map(|plan| {
+            plan.structure_names()
+                .into_iter()
+                .map(move |structure| (plan.id.as_str(), structure, &plan.dvhs[structure]))
+        })
+    }
+
+    /// Adds a plan to the patient.
+    ///
+    /// # Parameters
+    /// - `plan`: The plan to add
+    ///
+    /// # Errors
+    /// - `Error::DuplicatePlanId`: If the patient already has a plan with the same identifier
+    pub fn add_plan(&mut self, plan: Plan) -> crate::Result<()> {
+        if self.get_plan(&plan.id).is_some() {
+            return Err(Error::DuplicatePlanId { plan_id: plan.id });
+        }
+        self.plans.push(plan);
+        Ok(())
+    }
+
+    /// Validates the DVHs of all plans, reporting where a validation failure occurred.
+    ///
+    /// Unlike [`DvhCheck::dvh_check`], a failure is wrapped with the plan identifier
+    /// and the structure name of the offending DVH. Plans are checked in order and the
+    /// structures of a plan in alphabetical order; checking stops at the first failure.
+    ///
+    /// # Errors
+    /// - `Error::PlanError`: If a DVH fails validation. Its source is an
+    ///   `Error::StructureError` wrapping the error returned by [`DvhCheck::dvh_check`].
+    pub fn dvh_check_all(&mut self) -> crate::Result<()> {
+        for plan in self.plans.iter_mut() {
+            let mut dvhs = plan.dvhs.iter_mut().collect::<Vec<_>>();
+            dvhs.sort_by(|a, b| a.0.cmp(b.0));
+            for (structure, dvh) in dvhs {
+                dvh.dvh_check().map_err(|source| Error::PlanError {
+                    plan_id: plan.id.clone(),
+                    source: Box::new(Error::StructureError {
+                        structure: structure.clone(),
+                        source: Box::new(source),
+                    }),
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl DvhCheck for Patient {
     fn dvh_check(&mut self) -> crate::Result<()> {
         for plan in self.plans.iter_mut() {
@@ -32,14 +132,13 @@ impl DvhCheck for Patient {
     }
 }
 
-#[cfg(all(test, feature = "serde"))]
+#[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Dvh;
-    use serde_json;
     use std::collections::HashMap;
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_patient_serialize_to_json() {
         let mut dvhs = HashMap::new();
         dvhs.insert(
@@ -71,6 +170,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_patient_deserialize_from_json() {
         let json = r#"{
             "patient_id": "P67890",
@@ -105,6 +205,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_patient_round_trip() {
         let original = Patient {
             patient_id: "P99999".to_string(),
@@ -135,6 +236,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_patient_serialize_minimal() {
         let patient = Patient {
             patient_id: "P00001".to_string(),
@@ -148,6 +250,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn test_patient_deserialize_missing_optional_fields() {
         let json = r#"{
             "patient_id": "P11111",
@@ -159,5 +262,142 @@ mod tests {
         assert!(patient.name.is_none());
         assert_eq!(patient.plans.len(), 0);
     }
-}
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_patient_dvh_check_all_reports_location() {
+        use crate::{DoseUnit, VolumeUnit};
+
+        let mut valid = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        valid.add_slice(&[0.0, 10.0], &[1.0, 0.0]);
+        let malformed: Dvh = serde_json::from_str(
+            r#"{"dose_unit":"Gy","volume_unit":"Percent","d":[0.0,10.0],"v":[150.0,0.0]}"#,
+        )
+        .unwrap();
+
+        let mut plan_dvhs = HashMap::new();
+        plan_dvhs.insert("PTV".to_string(), valid.clone());
+        plan_dvhs.insert("Rectum".to_string(), malformed);
+        let mut patient = Patient {
+            patient_id: "P12345".to_string(),
+            name: None,
+            plans: vec![
+                Plan {
+                    id: "PLAN001".to_string(),
+                    name: None,
+                    dvhs: HashMap::from([("PTV".to_string(), valid)]),
+                },
+                Plan {
+                    id: "PLAN002".to_string(),
+                    name: None,
+                    dvhs: plan_dvhs,
+                },
+            ],
+        };
+
+        let err = patient.dvh_check_all().unwrap_err();
+        let Error::PlanError { plan_id, source } = err else {
+            panic!("expected a plan error, got {err:?}");
+        };
+        assert_eq!(plan_id, "PLAN002");
+        let Error::StructureError { structure, source } = *source else {
+            panic!("expected a structure error");
+        };
+        assert_eq!(structure, "Rectum");
+        assert!(matches!(*source, Error::PercentVolumeOutOfRange));
+    }
+
+    #[test]
+    fn test_patient_get_plan() {
+        let mut patient = Patient {
+            patient_id: "P12345".to_string(),
+            name: None,
+            plans: vec![
+                Plan {
+                    id: "PLAN001".to_string(),
+                    ..Default::default()
+                },
+                Plan {
+                    id: "PLAN002".to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        assert_eq!(patient.get_plan("PLAN002").unwrap().id, "PLAN002");
+        assert!(patient.get_plan("PLAN003").is_none());
+        assert!(patient.get_plan_mut("PLAN003").is_none());
+
+        patient.get_plan_mut("PLAN001").unwrap().name = Some("Boost".to_string());
+        assert_eq!(patient.plans[0].name.as_deref(), Some("Boost"));
+    }
+
+    #[test]
+    fn test_patient_add_plan() {
+        let plan = |id: &str| Plan {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        let mut patient = Patient::default();
+        patient.add_plan(plan("PLAN001")).unwrap();
+        patient.add_plan(plan("PLAN002")).unwrap();
+        assert_eq!(patient.plans.len(), 2);
+
+        let result = patient.add_plan(plan("PLAN001"));
+        assert!(matches!(
+            result,
+            Err(Error::DuplicatePlanId { plan_id }) if plan_id == "PLAN001"
+        ));
+        assert_eq!(patient.plans.len(), 2);
+    }
+
+    #[test]
+    fn test_patient_all_dvhs() {
+        let plan = |id: &str| Plan {
+            id: id.to_string(),
+            name: None,
+            dvhs: HashMap::from([
+                ("Rectum".to_string(), Dvh::default()),
+                ("PTV".to_string(), Dvh::default()),
+            ]),
+        };
+        let patient = Patient {
+            plans: vec![plan("PLAN001"), plan("PLAN002")],
+            ..Default::default()
+        };
+
+        let ids = patient
+            .all_dvhs()
+            .map(|(plan_id, structure, _)| (plan_id, structure))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                ("PLAN001", "PTV"),
+                ("PLAN001", "Rectum"),
+                ("PLAN002", "PTV"),
+                ("PLAN002", "Rectum"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_patient_find_dvh() {
+        use crate::{DoseUnit, VolumeUnit};
+
+        let mut ptv = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        ptv.add_slice(&[0.0, 60.0], &[1.0, 0.0]);
+        let patient = Patient {
+            plans: vec![Plan {
+                id: "PLAN001".to_string(),
+                name: None,
+                dvhs: HashMap::from([("PTV".to_string(), ptv.clone())]),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(patient.find_dvh("PLAN001", "PTV"), Some(&ptv));
+        assert!(patient.find_dvh("PLAN002", "PTV").is_none());
+        assert!(patient.find_dvh("PLAN001", "Rectum").is_none());
+    }
+}
@@ -3,8 +3,12 @@
 //! This module provides the [`Plan`] struct for representing radiation therapy
 //! treatment plans, including their associated dose-volume histograms.
 
+use crate::Error;
 use crate::traits::DvhCheck;
-use crate::{Dvh, MaxDose};
+use crate::{
+    Constraint, DoseUnit, Dvh, MaxDose, MeanDose, Metric, MinDose, ReportRow, ReportTable, RoiType,
+    VolumeUnit,
+};
 use std::collections::HashMap;
 
 /// Represents a radiation therapy treatment plan.
@@ -22,6 +26,341 @@ pub struct Plan {
     pub dvhs: HashMap<String, Dvh>,
 }
 
+/// Represents the difference of a metric for a structure present in two plans.
+///
+/// # Fields
+/// - `structure`: The structure name
+/// - `value`: The value of the metric in the first plan, or `None` if it could not be
+///   evaluated
+/// - `other_value`: The value of the metric in the second plan, or `None` if it could not
+///   be evaluated
+/// - `delta`: The difference `other_value - value`, or `None` if either value is missing
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructureDiff {
+    pub structure: String,
+    pub value: Option<f64>,
+    pub other_value: Option<f64>,
+    pub delta: Option<f64>,
+}
+
+/// Represents the structure-by-structure difference of a metric between two plans.
+///
+/// # Fields
+/// - `structures`: The differences for the structures present in both plans
+/// - `only_in_self`: The structures only present in the first plan
+/// - `only_in_other`: The structures only present in the second plan
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlanDiff {
+    pub structures: Vec<StructureDiff>,
+    pub only_in_self: Vec<String>,
+    pub only_in_other: Vec<String>,
+}
+
+impl Plan {
+    /// Returns the DVH of a structure.
+    ///
+    /// # Parameters
+    /// - `structure`: The structure name
+    ///
+    /// # Returns
+    /// The DVH of the structure, or `None` if the plan has no such structure
+    pub fn get_dvh(&self, structure: &str) -> Option<&Dvh> {
+        self.dvhs.get(structure)
+    }
+
+    /// Returns the DVH of a structure for modification.
+    ///
+    /// # Parameters
+    /// - `structure`: The structure name
+    ///
+    /// # Returns
+    /// The DVH of the structure, or `None` if the plan has no such structure
+    pub fn get_dvh_mut(&mut self, structure: &str) -> Option<&mut Dvh> {
+        self.dvhs.get_mut(structure)
+    }
+
+    /// Validates a DVH and adds it to the plan.
+    ///
+    /// The DVH is checked, and thereby sorted, with [`DvhCheck::dvh_check`] before it is
+    /// inserted. An existing DVH of the same structure is replaced.
+    ///
+    /// # Parameters
+    /// - `structure`: The structure name
+    /// - `dvh`: The DVH of the structure
+    ///
+    /// # Errors
+    /// - Any error returned by [`DvhCheck::dvh_check`]; the plan is left unchanged
+    pub fn add_dvh(&mut self, structure: impl Into<String>, mut dvh: Dvh) -> crate::Result<()> {
+        dvh.dvh_check()?;
+        self.dvhs.insert(structure.into(), dvh);
+        Ok(())
+    }
+
+    /// Returns the names of the structures in the plan.
+    ///
+    /// # Returns
+    /// The structure names in alphabetical order
+    pub fn structure_names(&self) -> Vec<&str> {
+        let mut names = self.dvhs.keys().map(String::as_str).collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// Finds the organ at risk with the highest value of a metric.
+    ///
+    /// Only structures whose DVH is tagged as [Oar](RoiType::Oar) are considered. If
+    /// none of the DVHs in the plan is tagged as [Oar](RoiType::Oar), all structures not
+    /// tagged as [Target](RoiType::Target) are considered. The metric is evaluated in
+    /// [Gy](DoseUnit::Gy), so structures with different dose units are ranked
+    /// consistently. Structures for which the metric cannot be evaluated are skipped.
+    ///
+    /// # Parameters
+    /// - `metric`: The metric used to rank the structures
+    ///
+    /// # Returns
+    /// The structure name and metric value of the worst organ at risk, or `None` if no
+    /// structure qualifies
+    pub fn worst_oar_by(&self, metric: Metric) -> Option<(String, f64)> {
+        let has_oars = self
+            .dvhs
+            .values()
+            .any(|dvh| dvh.roi_type == Some(RoiType::Oar));
+        self.dvhs
+            .iter()
+            .filter(|(_, dvh)| {
+                if has_oars {
+                    dvh.roi_type == Some(RoiType::Oar)
+                } else {
+                    dvh.roi_type != Some(RoiType::Target)
+                }
+            })
+            .filter_map(|(name, dvh)| {
+                metric
+                    .evaluate(&dvh.to_dose_unit(DoseUnit::Gy), None)
+                    .ok()
+                    .map(|value| (name.clone(), value))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+    }
+
+    /// Evaluates the default metrics of every structure according to its type.
+    ///
+    /// The metrics are selected with [`Dvh::default_metrics_for`]; structures without a
+    /// [RoiType] tag are treated as [Other](RoiType::Other). Doses are reported in
+    /// [Gy](DoseUnit::Gy). The rows are ordered by structure name, then by metric in the
+    /// order of the default set.
+    ///
+    /// # Parameters
+    /// - `volumes`: The structure volumes in cc, mapped by structure name. Structures
+    ///   without an entry fall back to the volume resolution of [`Metric::evaluate`].
+    ///
+    /// # Returns
+    /// A report table with one row per structure and metric. Metrics that cannot be
+    /// evaluated have no value.
+    pub fn auto_report(&self, volumes: &HashMap<String, f64>) -> ReportTable {
+        let mut names = self.dvhs.keys().collect::<Vec<_>>();
+        names.sort();
+        let mut rows = Vec::new();
+        for name in names {
+            let dvh = self.dvhs[name].to_dose_unit(DoseUnit::Gy);
+            let volume = volumes.get(name).copied();
+            for metric in Dvh::default_metrics_for(dvh.roi_type.unwrap_or(RoiType::Other)) {
+                rows.push(ReportRow {
+                    structure: name.clone(),
+                    metric,
+                    value: metric.evaluate(&dvh, volume).ok(),
+                });
+            }
+        }
+        ReportTable { rows }
+    }
+
+    /// Compares a metric structure by structure between this plan and another plan.
+    ///
+    /// Doses are compared in [Gy](DoseUnit::Gy). All lists in the result are ordered by
+    /// structure name.
+    ///
+    /// # Parameters
+    /// - `other`: The plan to compare with, e.g. the adapted plan
+    /// - `metric`: The metric to compare
+    /// - `volumes`: The structure volumes in cc, mapped by structure name and used for
+    ///   both plans. Structures without an entry fall back to the volume resolution of
+    ///   [`Metric::evaluate`].
+    ///
+    /// # Returns
+    /// The metric differences for the structures present in both plans, and the names
+    /// of the structures present in only one of them
+    pub fn diff(&self, other: &Plan, metric: Metric, volumes: &HashMap<String, f64>) -> PlanDiff {
+        let evaluate = |dvh: &Dvh, name: &String| {
+            metric
+                .evaluate(&dvh.to_dose_unit(DoseUnit::Gy), volumes.get(name).copied())
+                .ok()
+        };
+        let mut names = self.dvhs.keys().collect::<Vec<_>>();
+        names.sort();
+        let mut diff = PlanDiff::default();
+        for name in names {
+            let Some(other_dvh) = other.dvhs.get(name) else {
+                diff.only_in_self.push(name.clone());
+                continue;
+            };
+            let value = evaluate(&self.dvhs[name], name);
+            let other_value = evaluate(other_dvh, name);
+            diff.structures.push(StructureDiff {
+                structure: name.clone(),
+                value,
+                other_value,
+                delta: value.zip(other_value).map(|(a, b)| b - a),
+            });
+        }
+        diff.only_in_other = other
+            .dvhs
+            .keys()
+            .filter(|name| !self.dvhs.contains_key(*name))
+            .cloned()
+            .collect();
+        diff.only_in_other.sort();
+        diff
+    }
+
+    /// Checks whether a structure of the plan satisfies a constraint.
+    ///
+    /// # Parameters
+    /// - `c`: The constraint to check
+    ///
+    /// # Returns
+    /// `true` if the constraint is satisfied
+    ///
+    /// # Errors
+    /// - `Error::StructureNotFound`: If the plan has no structure named `c.structure`
+    /// - Any error returned by [`Metric::evaluate`]
+    pub fn evaluate_constraint(&self, c: &Constraint) -> crate::Result<bool> {
+        let dvh = self
+            .dvhs
+            .get(&c.structure)
+            .ok_or_else(|| Error::StructureNotFound {
+                structure: c.structure.clone(),
+            })?;
+        let value = c.metric.evaluate(dvh, None)?;
+        Ok(c.comparison.holds(value, c.threshold))
+    }
+
+    /// Finds the structures whose total volume exceeds the volume of the body structure.
+    ///
+    /// Every structure should be contained in the external or body structure, so a
+    /// larger total volume signals a contouring or unit error. The total volume of a
+    /// structure is its reference volume, or the largest volume of a [Cc](VolumeUnit::Cc)
+    /// DVH. Structures whose total volume cannot be determined are skipped.
+    ///
+    /// # Parameters
+    /// - `body`: The name of the external or body structure
+    ///
+    /// # Returns
+    /// The names of the structures larger than the body, in alphabetical order
+    ///
+    /// # Errors
+    /// - `Error::StructureNotFound`: If the plan has no structure named `body`
+    /// - `Error::VolumeUnitConversionRequired`: If the total volume of the body cannot be
+    ///   determined
+    /// - `Error::InvalidTotalVolume`: If the total volume of the body is not positive
+    pub fn check_volume_containment(&self, body: &str) -> crate::Result<Vec<String>> {
+        let body_volume = self
+            .dvhs
+            .get(body)
+            .ok_or_else(|| Error::StructureNotFound {
+                structure: body.to_string(),
+            })?
+            .resolve_total_volume(None)?;
+        let mut names = self
+            .dvhs
+            .iter()
+            .filter(|(name, dvh)| {
+                name.as_str() != body
+                    && dvh
+                        .resolve_total_volume(None)
+                        .is_ok_and(|volume| volume > body_volume)
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Copies the DVH of a structure to a new structure name.
+    ///
+    /// The copy is independent of the original, so it can be modified for "what if"
+    /// scenario analysis.
+    ///
+    /// # Parameters
+    /// - `from`: The name of the structure to copy
+    /// - `to`: The name of the new structure
+    ///
+    /// # Errors
+    /// - `Error::StructureNotFound`: If the plan has no structure named `from`
+    /// - `Error::DuplicateStructure`: If the plan already has a structure named `to`
+    pub fn clone_structure(&mut self, from: &str, to: &str) -> crate::Result<()> {
+        let dvh = self
+            .dvhs
+            .get(from)
+            .ok_or_else(|| Error::StructureNotFound {
+                structure: from.to_string(),
+            })?
+            .clone();
+        if self.dvhs.contains_key(to) {
+            return Err(Error::DuplicateStructure {
+                structure: to.to_string(),
+            });
+        }
+        self.dvhs.insert(to.to_string(), dvh);
+        Ok(())
+    }
+
+    /// Converts every DVH in the plan to the given dose and volume units.
+    ///
+    /// Converting between [Percent](VolumeUnit::Percent) and [Cc](VolumeUnit::Cc) volumes
+    /// requires the structure volume, which is looked up in `structure_volumes` by
+    /// structure name and falls back to the reference volume of the DVH. The plan is
+    /// left unchanged if any DVH fails to convert.
+    ///
+    /// # Parameters
+    /// - `dose_unit`: The target dose unit
+    /// - `volume_unit`: The target volume unit
+    /// - `structure_volumes`: The structure volumes in cc, mapped by structure name
+    ///
+    /// # Errors
+    /// - `Error::VolumeUnitConversionRequired`: If a structure volume is needed but unknown
+    /// - Any error returned by [`Dvh::to_absolute_volume`] or [`Dvh::to_percent_volume`]
+    pub fn normalize_units(
+        &mut self,
+        dose_unit: DoseUnit,
+        volume_unit: VolumeUnit,
+        structure_volumes: &HashMap<String, f64>,
+    ) -> crate::Result<()> {
+        let mut dvhs = HashMap::with_capacity(self.dvhs.len());
+        for (name, dvh) in &self.dvhs {
+            let dvh = if dvh.volume_unit == volume_unit {
+                dvh.clone()
+            } else {
+                // Don't fall back to the maximum volume of a Cc DVH as structure volume.
+                let structure_volume = structure_volumes
+                    .get(name)
+                    .copied()
+                    .or(dvh.reference_volume_cc)
+                    .ok_or(Error::VolumeUnitConversionRequired)?;
+                match volume_unit {
+                    VolumeUnit::Cc => dvh.to_absolute_volume(Some(structure_volume))?,
+                    VolumeUnit::Percent => dvh.to_percent_volume(Some(structure_volume))?,
+                }
+            };
+            dvhs.insert(name.clone(), dvh.to_dose_unit(dose_unit));
+        }
+        self.dvhs = dvhs;
+        Ok(())
+    }
+}
+
 impl DvhCheck for Plan {
     fn dvh_check(&mut self) -> crate::Result<()> {
         for dvh in self.dvhs.values_mut() {
@@ -40,3 +379,382 @@ impl MaxDose for Plan {
             .unwrap_or(0.0)
     }
 }
+
+impl MeanDose for Plan {
+    /// Returns the maximum of the mean doses of the structures.
+    ///
+    /// Structures for which [`Dvh::mean_dose`] fails, e.g. empty or unsorted DVHs, are
+    /// skipped.
+    ///
+    /// # Errors
+    /// - `Error::DvhNoData`: If no structure has a mean dose
+    fn mean_dose(&self) -> crate::Result<f64> {
+        self.dvhs
+            .values()
+            .filter_map(|dvh| dvh.mean_dose().ok())
+            .max_by(f64::total_cmp)
+            .ok_or(Error::DvhNoData)
+    }
+}
+
+impl MinDose for Plan {
+    /// Returns the minimum of the minimum doses of the structures.
+    ///
+    /// Structures without any volume are skipped. If no structure has a volume, 0.0 is
+    /// returned.
+    fn min_dose(&self) -> f64 {
+        self.dvhs
+            .values()
+            .filter(|dvh| dvh.volumes().iter().any(|&v| v > 0.0))
+            .map(|dvh| dvh.min_dose())
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_ulps_eq;
+
+    fn dvh(doses: &[f64], volumes: &[f64], roi_type: Option<RoiType>) -> Dvh {
+        let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        dvh.add_slice(doses, volumes);
+        dvh.roi_type = roi_type;
+        dvh.dvh_check().unwrap();
+        dvh
+    }
+
+    #[test]
+    fn test_worst_oar_by_tagged() {
+        let mut plan = Plan::default();
+        plan.dvhs.insert(
+            "PTV".to_string(),
+            dvh(&[0.0, 60.0, 63.0], &[1.0, 0.95, 0.0], Some(RoiType::Target)),
+        );
+        plan.dvhs.insert(
+            "Rectum".to_string(),
+            dvh(&[0.0, 30.0, 55.0], &[1.0, 0.4, 0.0], Some(RoiType::Oar)),
+        );
+        plan.dvhs.insert(
+            "Bladder".to_string(),
+            dvh(&[0.0, 30.0, 50.0], &[1.0, 0.2, 0.0], Some(RoiType::Oar)),
+        );
+
+        let (name, value) = plan.worst_oar_by(Metric::MaxDose).unwrap();
+        assert_eq!(name, "Rectum");
+        assert_ulps_eq!(value, 55.0);
+
+        let (name, value) = plan.worst_oar_by(Metric::Vx(30.0)).unwrap();
+        assert_eq!(name, "Rectum");
+        assert_ulps_eq!(value, 0.4);
+    }
+
+    #[test]
+    fn test_worst_oar_by_untagged_considers_all() {
+        let mut plan = Plan::default();
+        plan.dvhs
+            .insert("Rectum".to_string(), dvh(&[0.0, 55.0], &[1.0, 0.0], None));
+        plan.dvhs
+            .insert("Bladder".to_string(), dvh(&[0.0, 50.0], &[1.0, 0.0], None));
+
+        let (name, _) = plan.worst_oar_by(Metric::MaxDose).unwrap();
+        assert_eq!(name, "Rectum");
+        assert!(Plan::default().worst_oar_by(Metric::MaxDose).is_none());
+    }
+
+    #[test]
+    fn test_worst_oar_by_mixed_dose_units() {
+        let mut plan = Plan::default();
+        plan.dvhs
+            .insert("Rectum".to_string(), dvh(&[0.0, 45.0], &[1.0, 0.0], None));
+        let mut bladder = Dvh::new(DoseUnit::CGy, VolumeUnit::Percent);
+        bladder.add_slice(&[0.0, 2000.0], &[1.0, 0.0]);
+        bladder.dvh_check().unwrap();
+        plan.dvhs.insert("Bladder".to_string(), bladder);
+
+        let (name, value) = plan.worst_oar_by(Metric::MaxDose).unwrap();
+        assert_eq!(name, "Rectum");
+        assert_ulps_eq!(value, 45.0);
+    }
+
+    #[test]
+    fn test_worst_oar_by_tagged_target_untagged_oars() {
+        let mut plan = Plan::default();
+        plan.dvhs.insert(
+            "PTV".to_string(),
+            dvh(&[0.0, 60.0, 63.0], &[1.0, 0.95, 0.0], Some(RoiType::Target)),
+        );
+        plan.dvhs
+            .insert("Rectum".to_string(), dvh(&[0.0, 55.0], &[1.0, 0.0], None));
+        plan.dvhs
+            .insert("Bladder".to_string(), dvh(&[0.0, 50.0], &[1.0, 0.0], None));
+
+        let (name, value) = plan.worst_oar_by(Metric::MaxDose).unwrap();
+        assert_eq!(name, "Rectum");
+        assert_ulps_eq!(value, 55.0);
+    }
+
+    #[test]
+    fn test_normalize_units_mixed_dose_units() {
+        let mut plan = Plan::default();
+        plan.dvhs
+            .insert("PTV".to_string(), dvh(&[0.0, 60.0], &[1.0, 0.0], None));
+        let mut rectum = Dvh::new(DoseUnit::CGy, VolumeUnit::Cc);
+        rectum.add_slice(&[0.0, 2500.0, 5000.0], &[80.0, 40.0, 0.0]);
+        plan.dvhs.insert("Rectum".to_string(), rectum);
+
+        let volumes = HashMap::from([("PTV".to_string(), 200.0)]);
+        plan.normalize_units(DoseUnit::Gy, VolumeUnit::Cc, &volumes)
+            .unwrap();
+
+        let ptv = &plan.dvhs["PTV"];
+        assert_eq!(ptv.dose_unit, DoseUnit::Gy);
+        assert_eq!(ptv.volume_unit, VolumeUnit::Cc);
+        assert_eq!(ptv.volumes(), &[200.0, 0.0]);
+
+        let rectum = &plan.dvhs["Rectum"];
+        assert_eq!(rectum.dose_unit, DoseUnit::Gy);
+        assert_eq!(rectum.doses(), &[0.0, 25.0, 50.0]);
+        assert_eq!(rectum.volumes(), &[80.0, 40.0, 0.0]);
+    }
+
+    #[test]
+    fn test_normalize_units_missing_structure_volume() {
+        let mut plan = Plan::default();
+        plan.dvhs
+            .insert("PTV".to_string(), dvh(&[0.0, 60.0], &[1.0, 0.0], None));
+        let original = plan.clone();
+
+        let result = plan.normalize_units(DoseUnit::Gy, VolumeUnit::Cc, &HashMap::new());
+        assert!(matches!(result, Err(Error::VolumeUnitConversionRequired)));
+        assert_eq!(plan, original);
+
+        let mut rectum = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+        rectum.add_slice(&[0.0, 25.0, 50.0], &[80.0, 40.0, 0.0]);
+        plan.dvhs.insert("Rectum".to_string(), rectum);
+        plan.dvhs.remove("PTV");
+        let original = plan.clone();
+        let result = plan.normalize_units(DoseUnit::Gy, VolumeUnit::Percent, &HashMap::new());
+        assert!(matches!(result, Err(Error::VolumeUnitConversionRequired)));
+        assert_eq!(plan, original);
+    }
+
+    #[test]
+    fn test_auto_report_target_metrics() {
+        let mut plan = Plan::default();
+        plan.dvhs.insert(
+            "PTV".to_string(),
+            dvh(&[0.0, 50.0, 60.0], &[1.0, 1.0, 0.0], Some(RoiType::Target)),
+        );
+        plan.dvhs.insert(
+            "Rectum".to_string(),
+            dvh(&[0.0, 40.0], &[1.0, 0.0], Some(RoiType::Oar)),
+        );
+
+        let report = plan.auto_report(&HashMap::new());
+        let metrics = report
+            .rows_for("PTV")
+            .map(|row| row.metric)
+            .collect::<Vec<_>>();
+        assert_eq!(metrics, Dvh::default_metrics_for(RoiType::Target));
+        let d95 = report.rows_for("PTV").next().unwrap();
+        assert_ulps_eq!(d95.value.unwrap(), 50.5);
+        assert_eq!(report.rows_for("Rectum").count(), 4);
+        assert_eq!(report.rows.len(), 7);
+    }
+
+    #[test]
+    fn test_check_volume_containment() {
+        let cc = |volume: f64| {
+            let mut dvh = Dvh::new(DoseUnit::Gy, VolumeUnit::Cc);
+            dvh.add_slice(&[0.0, 10.0], &[volume, 0.0]);
+            dvh
+        };
+        let mut plan = Plan::default();
+        plan.dvhs.insert("Body".to_string(), cc(5000.0));
+        plan.dvhs.insert("Liver".to_string(), cc(1500.0));
+        plan.dvhs.insert("PTV".to_string(), cc(50000.0));
+
+        assert_eq!(plan.check_volume_containment("Body").unwrap(), vec!["PTV"]);
+        assert!(matches!(
+            plan.check_volume_containment("External"),
+            Err(Error::StructureNotFound { structure }) if structure == "External"
+        ));
+    }
+
+    #[test]
+    fn test_clone_structure() {
+        let mut plan = Plan::default();
+        plan.dvhs.insert(
+            "Rectum".to_string(),
+            dvh(&[0.0, 40.0], &[1.0, 0.0], Some(RoiType::Oar)),
+        );
+
+        plan.clone_structure("Rectum", "Rectum_scenario").unwrap();
+        plan.dvhs.get_mut("Rectum_scenario").unwrap().add(50.0, 0.0);
+        assert_eq!(plan.dvhs["Rectum"].len(), 2);
+        assert_eq!(plan.dvhs["Rectum_scenario"].len(), 3);
+
+        assert!(matches!(
+            plan.clone_structure("Bladder", "Bladder_copy"),
+            Err(Error::StructureNotFound { .. })
+        ));
+        assert!(matches!(
+            plan.clone_structure("Rectum", "Rectum_scenario"),
+            Err(Error::DuplicateStructure { structure }) if structure == "Rectum_scenario"
+        ));
+    }
+
+    #[test]
+    fn test_plan_min_dose() {
+        let mut plan = Plan::default();
+        assert_ulps_eq!(plan.min_dose(), 0.0);
+        plan.dvhs.insert(
+            "PTV".to_string(),
+            dvh(&[0.0, 50.0, 60.0], &[0.0, 1.0, 0.0], None),
+        );
+        plan.dvhs.insert(
+            "Rectum".to_string(),
+            dvh(&[0.0, 10.0, 40.0], &[0.0, 1.0, 0.0], None),
+        );
+        plan.dvhs.insert("Empty".to_string(), Dvh::default());
+        assert_ulps_eq!(plan.min_dose(), 10.0);
+    }
+
+    #[test]
+    fn test_plan_diff() {
+        let mut old = Plan::default();
+        old.dvhs.insert(
+            "PTV".to_string(),
+            dvh(&[0.0, 50.0, 60.0], &[1.0, 1.0, 0.0], Some(RoiType::Target)),
+        );
+        old.dvhs
+            .insert("Rectum".to_string(), dvh(&[0.0, 40.0], &[1.0, 0.0], None));
+        let mut new = Plan::default();
+        new.dvhs.insert(
+            "PTV".to_string(),
+            dvh(&[0.0, 55.0, 60.0], &[1.0, 1.0, 0.0], Some(RoiType::Target)),
+        );
+        new.dvhs
+            .insert("Bladder".to_string(), dvh(&[0.0, 30.0], &[1.0, 0.0], None));
+
+        let diff = old.diff(&new, Metric::Dx(0.95), &HashMap::new());
+        assert_eq!(diff.structures.len(), 1);
+        let ptv = &diff.structures[0];
+        assert_eq!(ptv.structure, "PTV");
+        assert_ulps_eq!(ptv.value.unwrap(), 50.5);
+        assert_ulps_eq!(ptv.other_value.unwrap(), 55.25);
+        assert_ulps_eq!(ptv.delta.unwrap(), 4.75);
+        assert_eq!(diff.only_in_self, vec!["Rectum"]);
+        assert_eq!(diff.only_in_other, vec!["Bladder"]);
+    }
+
+    #[test]
+    fn test_plan_get_dvh_and_structure_names() {
+        let mut plan = Plan::default();
+        plan.dvhs
+            .insert("Rectum".to_string(), dvh(&[0.0, 40.0], &[1.0, 0.0], None));
+        plan.dvhs
+            .insert("Bladder".to_string(), dvh(&[0.0, 30.0], &[1.0, 0.0], None));
+
+        assert_ulps_eq!(plan.get_dvh("Rectum").unwrap().max_dose(), 40.0);
+        assert!(plan.get_dvh("PTV").is_none());
+        plan.get_dvh_mut("Bladder").unwrap().roi_type = Some(RoiType::Oar);
+        assert_eq!(plan.dvhs["Bladder"].roi_type, Some(RoiType::Oar));
+        assert!(plan.get_dvh_mut("PTV").is_none());
+
+        assert_eq!(plan.structure_names(), vec!["Bladder", "Rectum"]);
+    }
+
+    #[test]
+    fn test_plan_add_dvh() {
+        let mut plan = Plan::default();
+        let mut rectum = Dvh::new(DoseUnit::Gy, VolumeUnit::Percent);
+        rectum.add_slice(&[40.0, 0.0, 20.0], &[0.0, 1.0, 0.5]);
+        plan.add_dvh("Rectum", rectum).unwrap();
+        assert_eq!(plan.dvhs["Rectum"].doses(), &[0.0, 20.0, 40.0]);
+        assert_ulps_eq!(plan.dvhs["Rectum"].vx(10.0).unwrap(), 0.75);
+
+        plan.add_dvh("Rectum", dvh(&[0.0, 50.0], &[1.0, 0.0], None))
+            .unwrap();
+        assert_ulps_eq!(plan.dvhs["Rectum"].max_dose(), 50.0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_plan_add_dvh_rejects_invalid() {
+        // `add` rejects negative volumes, so deserialize the malformed DVH instead.
+        let invalid: Dvh = serde_json::from_str(
+            r#"{"dose_unit":"Gy","volume_unit":"Cc","d":[0.0,10.0],"v":[5.0,-1.0]}"#,
+        )
+        .unwrap();
+        let mut plan = Plan::default();
+        let result = plan.add_dvh("Rectum", invalid);
+        assert!(matches!(result, Err(Error::NegativeVolume)));
+        assert!(plan.dvhs.is_empty());
+    }
+
+    #[test]
+    fn test_mean_dose_trait_object() {
+        let rectum = dvh(&[0.0, 40.0], &[1.0, 1.0], None);
+        let bladder = dvh(&[0.0, 30.0], &[1.0, 1.0], None);
+        let mut plan = Plan::default();
+        plan.dvhs.insert("Rectum".to_string(), rectum.clone());
+        plan.dvhs.insert("Bladder".to_string(), bladder);
+
+        let dvh: &dyn MeanDose = &rectum;
+        assert_ulps_eq!(dvh.mean_dose().unwrap(), 40.0);
+        let plan: &dyn MeanDose = &plan;
+        assert_ulps_eq!(plan.mean_dose().unwrap(), 40.0);
+        assert!(matches!(
+            MeanDose::mean_dose(&Plan::default()),
+            Err(Error::DvhNoData)
+        ));
+    }
+
+    #[test]
+    fn test_mean_dose_skips_structures_without_data() {
+        let mut plan = Plan::default();
+        plan.dvhs.insert(
+            "Empty".to_string(),
+            Dvh::new(DoseUnit::Gy, VolumeUnit::Percent),
+        );
+        assert!(matches!(MeanDose::mean_dose(&plan), Err(Error::DvhNoData)));
+
+        plan.dvhs
+            .insert("Bladder".to_string(), dvh(&[0.0, 30.0], &[1.0, 1.0], None));
+        assert_ulps_eq!(MeanDose::mean_dose(&plan).unwrap(), 30.0);
+    }
+
+    #[test]
+    fn test_evaluate_constraint() {
+        use crate::Comparison;
+
+        let mut plan = Plan::default();
+        plan.dvhs.insert(
+            "Rectum".to_string(),
+            dvh(
+                &[0.0, 60.0, 70.0, 75.0],
+                &[1.0, 0.3, 0.1, 0.0],
+                Some(RoiType::Oar),
+            ),
+        );
+        let mut constraint = Constraint {
+            structure: "Rectum".to_string(),
+            metric: Metric::Vx(70.0),
+            comparison: Comparison::LessOrEqual,
+            threshold: 0.15,
+        };
+        assert!(plan.evaluate_constraint(&constraint).unwrap());
+
+        constraint.metric = Metric::Vx(60.0);
+        assert!(!plan.evaluate_constraint(&constraint).unwrap());
+
+        constraint.structure = "Bladder".to_string();
+        assert!(matches!(
+            plan.evaluate_constraint(&constraint),
+            Err(Error::StructureNotFound { structure }) if structure == "Bladder"
+        ));
+    }
+}
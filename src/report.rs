@@ -0,0 +1,43 @@
+//! Tabular metric reports.
+//!
+//! This module provides the [`ReportTable`] struct holding metric values evaluated
+//! on the structures of a treatment plan.
+
+use crate::Metric;
+
+/// Represents a single metric value of a structure in a [ReportTable].
+///
+/// # Fields
+/// - `structure`: The structure name
+/// - `metric`: The evaluated metric
+/// - `value`: The value of the metric, or `None` if it could not be evaluated
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportRow {
+    pub structure: String,
+    pub metric: Metric,
+    pub value: Option<f64>,
+}
+
+/// Represents a table of metric values, one row per structure and metric.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportTable {
+    /// The rows of the table.
+    pub rows: Vec<ReportRow>,
+}
+
+impl ReportTable {
+    /// Returns the rows of a structure.
+    ///
+    /// # Parameters
+    /// - `structure`: The structure name
+    ///
+    /// # Returns
+    /// An iterator over the rows of the structure, in table order
+    pub fn rows_for<'a>(&'a self, structure: &'a str) -> impl Iterator<Item = &'a ReportRow> {
+        self.rows
+            .iter()
+            .filter(move |row| row.structure == structure)
+    }
+}
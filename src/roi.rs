@@ -0,0 +1,20 @@
+//! Region of interest classification.
+//!
+//! This module provides the [`RoiType`] enum for tagging the structure a
+//! dose-volume histogram belongs to with its clinical role.
+
+/// Represents the clinical role of a region of interest (ROI).
+///
+/// # Variants
+/// - `Target`: A target volume (e.g. GTV, CTV, PTV)
+/// - `Oar`: An organ at risk
+/// - `External`: The external contour or body outline
+/// - `Other`: Any other structure (e.g. support structures, helper volumes)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoiType {
+    Target,
+    Oar,
+    External,
+    Other,
+}
@@ -22,7 +22,6 @@ pub trait DvhCheck {
     /// - DVH data contains invalid values (e.g., negative doses or volumes)
     /// - Volume values are not within the valid range (0.0 to 1.0) if the volume type is [Percent](dvh::VolumeType::Percent)
     /// - Data structures are inconsistent and cannot be automatically corrected
-
     fn dvh_check(&mut self) -> crate::Result<()>;
 }
 
@@ -35,4 +34,30 @@ pub trait MaxDose {
     /// Returns the maximum dose value as an `f64`. If no dose data is available
     /// or the collection is empty, the behavior is to return 0.0;
     fn max_dose(&self) -> f64;
+}
+
+/// Trait for computing the minimum dose for the implementing type.
+pub trait MinDose {
+    /// Computes and returns the minimum dose that still receives a nonzero volume.
+    ///
+    /// # Returns
+    ///
+    /// Returns the minimum dose value as an `f64`. If no dose data is available
+    /// or the collection is empty, the behavior is to return 0.0;
+    fn min_dose(&self) -> f64;
+}
+
+/// Trait for computing the mean dose for the implementing type.
+pub trait MeanDose {
+    /// Computes and returns the volume-weighted mean dose.
+    ///
+    /// # Returns
+    ///
+    /// Returns the mean dose value as an `f64`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the mean dose cannot be computed, e.g.
+    /// because there is no dose data or the data is not sorted.
+    fn mean_dose(&self) -> crate::Result<f64>;
 }
\ No newline at end of file
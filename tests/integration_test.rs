@@ -2,6 +2,7 @@
 use dvh::Patient;
 #[cfg(feature = "serde")]
 use std::fs;
+#[cfg(feature = "serde")]
 use dvh::DvhCheck;
 
 #[test]